@@ -0,0 +1,20 @@
+use anyhow::Result;
+
+use crate::model::CodegenModel;
+use crate::output::GeneratedFiles;
+use crate::Codegen;
+
+/// Turns the discovered types into files for one target, eg: [`crate::UnityBackend`] for
+/// the C# authoring components. Each backend gets its own output folder and file manifest,
+/// so writing only what changed and check mode work the same for all of them.
+pub trait CodegenBackend: Send + Sync {
+    /// Shown in errors.
+    fn name(&self) -> &str;
+
+    fn generate(
+        &self,
+        config: &Codegen,
+        model: &CodegenModel,
+        files: &mut GeneratedFiles,
+    ) -> Result<()>;
+}
@@ -0,0 +1,238 @@
+use anyhow::Result;
+use std::fmt::Write;
+
+use crate::model::{AuthoringEnum, CodegenModel, FieldType};
+use crate::output::GeneratedFiles;
+use crate::{Codegen, CodegenBackend};
+
+const HEADER_FILE: &str = "unrust.h";
+
+/// A plain C header with the custom components as `repr(C)` structs and the wire enums,
+/// for engines or tools that talk to the game library without unity.
+pub struct CHeaderBackend;
+
+impl CodegenBackend for CHeaderBackend {
+    fn name(&self) -> &str {
+        "c header"
+    }
+
+    fn generate(
+        &self,
+        _config: &Codegen,
+        model: &CodegenModel,
+        files: &mut GeneratedFiles,
+    ) -> Result<()> {
+        let mut out = String::new();
+        writeln!(out, "// generated by unrust, do not edit")?;
+        writeln!(out, "#ifndef UNRUST_GENERATED_H")?;
+        writeln!(out, "#define UNRUST_GENERATED_H")?;
+        writeln!(out)?;
+        writeln!(out, "#include <stdbool.h>")?;
+        writeln!(out, "#include <stdint.h>")?;
+        writeln!(out)?;
+        writeln!(
+            out,
+            "// the elements of a Vec field, see unrust::UnityBuffer"
+        )?;
+        writeln!(out, "typedef struct UnrustBuffer {{")?;
+        writeln!(out, "    const void* ptr;")?;
+        writeln!(out, "    uintptr_t len;")?;
+        writeln!(out, "}} UnrustBuffer;")?;
+        writeln!(out)?;
+        writeln!(
+            out,
+            "// a String field, utf-8 like unity's FixedString64Bytes"
+        )?;
+        writeln!(out, "typedef struct UnrustString {{")?;
+        writeln!(out, "    uint16_t len;")?;
+        writeln!(out, "    uint8_t bytes[62];")?;
+        writeln!(out, "}} UnrustString;")?;
+        writeln!(out)?;
+        writeln!(
+            out,
+            "// a Handle or AssetPath field, relative to the unity Assets folder"
+        )?;
+        writeln!(out, "typedef struct UnrustAssetPath {{")?;
+        writeln!(out, "    uint16_t len;")?;
+        writeln!(out, "    uint8_t bytes[126];")?;
+        writeln!(out, "}} UnrustAssetPath;")?;
+        writeln!(out)?;
+        writeln!(
+            out,
+            "// a UnityCurve field, sampled between the first and last key"
+        )?;
+        writeln!(out, "typedef struct UnrustCurve {{")?;
+        writeln!(out, "    float start;")?;
+        writeln!(out, "    float end;")?;
+        writeln!(out, "    float samples[32];")?;
+        writeln!(out, "}} UnrustCurve;")?;
+        writeln!(out)?;
+        writeln!(
+            out,
+            "// a UnityGradient field, srgb rgba sampled from 0 to 1"
+        )?;
+        writeln!(out, "typedef struct UnrustGradient {{")?;
+        writeln!(out, "    float samples[16][4];")?;
+        writeln!(out, "}} UnrustGradient;")?;
+        writeln!(out)?;
+        writeln!(
+            out,
+            "// a UnityEntityRef field, the unity entity it was baked to"
+        )?;
+        writeln!(out, "typedef struct UnityEntity {{")?;
+        writeln!(out, "    int32_t index;")?;
+        writeln!(out, "    int32_t version;")?;
+        writeln!(out, "}} UnityEntity;")?;
+
+        for item in &model.enums {
+            write_enum(&mut out, item)?;
+        }
+
+        // resources are sent like components, after them
+        let components = || model.components.iter().chain(&model.resources);
+
+        for item in model.structs.iter().chain(components()) {
+            writeln!(out)?;
+            // C does not allow empty structs
+            if item.is_tag() {
+                writeln!(out, "// {} is a tag, sent without a value", item.name)?;
+                continue;
+            }
+            writeln!(out, "typedef struct {} {{", item.name)?;
+            for field in &item.fields {
+                writeln!(out, "    {};", c_field(&field.ty, &field.name))?;
+            }
+            writeln!(out, "}} {};", item.name)?;
+        }
+
+        writeln!(out)?;
+        writeln!(out, "typedef uint8_t CustomType;")?;
+        write_constants(
+            &mut out,
+            components().map(|component| format!("CustomType_{}", component.name)),
+        )?;
+
+        // C does not allow empty unions, with only tags there is no value
+        let payloads: Vec<_> = components()
+            .filter(|component| !component.is_tag())
+            .collect();
+        if !payloads.is_empty() {
+            writeln!(out)?;
+            writeln!(out, "typedef union CustomComponents {{")?;
+            for component in &payloads {
+                writeln!(out, "    {0} {0};", component.name)?;
+            }
+            writeln!(out, "}} CustomComponents;")?;
+        }
+
+        writeln!(out)?;
+        writeln!(out, "typedef struct CustomData {{")?;
+        writeln!(out, "    CustomType ty;")?;
+        if !payloads.is_empty() {
+            writeln!(out, "    CustomComponents value;")?;
+        }
+        writeln!(out, "}} CustomData;")?;
+
+        writeln!(out)?;
+        writeln!(out, "typedef uint8_t CustomStateType;")?;
+        write_constants(
+            &mut out,
+            model
+                .states
+                .iter()
+                .map(|state| format!("CustomStateType_{}", state.name)),
+        )?;
+
+        writeln!(out)?;
+        writeln!(out, "typedef struct CustomState {{")?;
+        writeln!(out, "    CustomStateType ty;")?;
+        writeln!(out, "    int8_t value;")?;
+        writeln!(out, "}} CustomState;")?;
+
+        for state in &model.states {
+            write_enum(&mut out, state)?;
+        }
+
+        for (index, prefab) in model.prefabs.iter().enumerate() {
+            writeln!(out)?;
+            writeln!(out, "#define {}_RESOURCE_ID {index}", prefab.name)?;
+            write_enum(&mut out, prefab)?;
+        }
+
+        writeln!(out)?;
+        writeln!(out, "#endif")?;
+
+        files.add_text(HEADER_FILE, out);
+        Ok(())
+    }
+}
+
+/// Unit enums are sent as their index, so they become a `uint8_t` plus one constant per variant.
+fn write_enum(out: &mut String, item: &AuthoringEnum) -> Result<()> {
+    writeln!(out)?;
+    writeln!(out, "typedef uint8_t {};", item.name)?;
+    write_constants(
+        out,
+        item.variants
+            .iter()
+            .map(|variant| format!("{}_{variant}", item.name)),
+    )
+}
+
+fn write_constants(out: &mut String, names: impl Iterator<Item = String>) -> Result<()> {
+    for (index, name) in names.enumerate() {
+        writeln!(out, "#define {name} {index}")?;
+    }
+
+    Ok(())
+}
+
+/// The glam types become float arrays, aligned like their simd versions in rust.
+fn c_field(ty: &FieldType, name: &str) -> String {
+    let len = match ty {
+        FieldType::Vec2 => 2,
+        FieldType::Vec3 => 3,
+        FieldType::Vec4 | FieldType::Quat | FieldType::Color => 4,
+        FieldType::Mat4 => 16,
+        FieldType::Array(ty, len) => return format!("{} {name}[{len}]", c_type(ty)),
+        _ => return format!("{} {name}", c_type(ty)),
+    };
+
+    match ty.align() {
+        4 => format!("float {name}[{len}]"),
+        align => format!("_Alignas({align}) float {name}[{len}]"),
+    }
+}
+
+fn c_type(ty: &FieldType) -> &str {
+    match ty {
+        FieldType::Bool => "bool",
+        FieldType::U8 => "uint8_t",
+        FieldType::I8 => "int8_t",
+        FieldType::U16 => "uint16_t",
+        FieldType::I16 => "int16_t",
+        FieldType::Char => "uint32_t",
+        FieldType::F32 => "float",
+        FieldType::F64 => "double",
+        FieldType::I32 => "int32_t",
+        FieldType::I64 => "int64_t",
+        FieldType::U32 => "uint32_t",
+        FieldType::U64 => "uint64_t",
+        FieldType::Vec2
+        | FieldType::Vec3
+        | FieldType::Vec4
+        | FieldType::Quat
+        | FieldType::Mat4
+        | FieldType::Color => "float",
+        FieldType::Enum(item) => &item.name,
+        FieldType::Struct(item) => &item.name,
+        // only scalars are allowed in arrays, see c_field
+        FieldType::Array(ty, _) => c_type(ty),
+        FieldType::String => "UnrustString",
+        FieldType::EntityRef => "UnityEntity",
+        FieldType::AssetPath | FieldType::Handle(_) => "UnrustAssetPath",
+        FieldType::Curve => "UnrustCurve",
+        FieldType::Gradient => "UnrustGradient",
+        FieldType::Vec(_) => "UnrustBuffer",
+    }
+}
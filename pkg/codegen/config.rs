@@ -0,0 +1,341 @@
+use anyhow::Context;
+use anyhow::Result;
+use serde::Deserialize;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use crate::{CHeaderBackend, CodegenBackend, UnityBackend};
+
+/// The `TransformUsageFlags` a generated Baker requests for its entity.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TransformUsage {
+    None,
+    Renderable,
+    Dynamic,
+    WorldSpace,
+    NonUniformScale,
+    ManualOverride,
+}
+
+impl TransformUsage {
+    pub(crate) fn csharp_name(&self) -> &'static str {
+        match self {
+            TransformUsage::None => "None",
+            TransformUsage::Renderable => "Renderable",
+            TransformUsage::Dynamic => "Dynamic",
+            TransformUsage::WorldSpace => "WorldSpace",
+            TransformUsage::NonUniformScale => "NonUniformScale",
+            TransformUsage::ManualOverride => "ManualOverride",
+        }
+    }
+
+    /// Parses the snake case name used in `unrust.toml` and `#[unity_authoring(transform = ..)]`.
+    pub(crate) fn from_name(name: &str) -> Option<TransformUsage> {
+        let usage = match name {
+            "none" => TransformUsage::None,
+            "renderable" => TransformUsage::Renderable,
+            "dynamic" => TransformUsage::Dynamic,
+            "world_space" => TransformUsage::WorldSpace,
+            "non_uniform_scale" => TransformUsage::NonUniformScale,
+            "manual_override" => TransformUsage::ManualOverride,
+            _ => return None,
+        };
+
+        Some(usage)
+    }
+}
+
+/// Where the per type authoring files are placed inside the output folder.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OutputLayout {
+    /// Every file directly in the output folder.
+    Flat,
+    /// Authoring files in subfolders mirroring the rust module path, eg: `types/enemies/BossAuthoring.cs`.
+    Modules,
+}
+
+/// The kinds of output codegen produces. The hooks and wire types are always written,
+/// a disabled type generator behaves as if no types of that kind exist. The order
+/// `unrust_setup` lists them in is still checked, the rust side numbers them either way.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Generator {
+    Components,
+    States,
+    Prefabs,
+    /// A json description of every type sent between unity and bevy, see `schema_file`.
+    Schema,
+}
+
+/// Configurable codegen. [`crate::generate_csharp`] is this with the defaults plus `unrust.toml`.
+/// The [`UnityBackend`] writes to the output folder given to [`Codegen::new`], more backends
+/// can be added with [`Codegen::backend`].
+///
+/// ```no_run
+/// unrust_codegen::Codegen::new("./src/lib.rs", "../Assets/unrust")
+///     .config_file("unrust.toml")?
+///     .namespace("mygame")
+///     .default_transform_usage(unrust_codegen::TransformUsage::Renderable)
+///     .backend(unrust_codegen::CHeaderBackend, "../include")
+///     .run()?;
+/// # Ok::<(), anyhow::Error>(())
+/// ```
+#[derive(Debug, Clone)]
+pub struct Codegen {
+    pub(crate) root: PathBuf,
+    pub(crate) backends: Vec<BackendOutput>,
+    pub(crate) namespace: String,
+    pub(crate) authoring_suffix: String,
+    pub(crate) layout: OutputLayout,
+    pub(crate) transform_usage: TransformUsage,
+    pub(crate) generators: Vec<Generator>,
+    pub(crate) hooks_file: String,
+    pub(crate) components_file: String,
+    pub(crate) states_file: String,
+    pub(crate) schema_file: String,
+    pub(crate) check: bool,
+}
+
+impl Codegen {
+    pub fn new(root: impl Into<PathBuf>, output: impl Into<PathBuf>) -> Codegen {
+        Codegen {
+            root: root.into(),
+            backends: vec![BackendOutput {
+                backend: Arc::new(UnityBackend),
+                output: output.into(),
+            }],
+            namespace: "unrust.userland".to_string(),
+            authoring_suffix: "Authoring".to_string(),
+            layout: OutputLayout::Flat,
+            transform_usage: TransformUsage::Dynamic,
+            generators: vec![
+                Generator::Components,
+                Generator::States,
+                Generator::Prefabs,
+                Generator::Schema,
+            ],
+            hooks_file: "UnrustHooks.cs".to_string(),
+            components_file: "UnrustComponent.cs".to_string(),
+            states_file: "UnrustState.cs".to_string(),
+            schema_file: "unrust-schema.json".to_string(),
+            check: false,
+        }
+    }
+
+    /// Applies the `[codegen]` table of a project config file, eg: `unrust.toml`.
+    /// Does nothing if the file does not exist. Relative paths in it are relative to the file.
+    pub fn config_file(mut self, path: impl AsRef<Path>) -> Result<Codegen> {
+        let path = path.as_ref();
+        if !path.exists() {
+            return Ok(self);
+        }
+
+        let contents = std::fs::read_to_string(path)
+            .with_context(|| format!("failed to read {}", path.display()))?;
+        let config: ProjectConfig = toml::from_str(&contents)
+            .with_context(|| format!("invalid config in {}", path.display()))?;
+
+        if std::env::var_os("OUT_DIR").is_some() {
+            println!("cargo:rerun-if-changed={}", path.display());
+        }
+
+        let Some(config) = config.codegen else {
+            return Ok(self);
+        };
+
+        let dir = path.parent().unwrap_or(Path::new("."));
+        if let Some(root) = config.root {
+            self.root = dir.join(root);
+        }
+        if let Some(output) = config.output {
+            for unity in self
+                .backends
+                .iter_mut()
+                .filter(|b| b.backend.name() == "unity")
+            {
+                unity.output = dir.join(&output);
+            }
+        }
+        if let Some(output) = config.c_header {
+            self = self.backend(CHeaderBackend, dir.join(output));
+        }
+        if let Some(namespace) = config.namespace {
+            self.namespace = namespace;
+        }
+        if let Some(suffix) = config.authoring_suffix {
+            self.authoring_suffix = suffix;
+        }
+        if let Some(layout) = config.layout {
+            self.layout = layout;
+        }
+        if let Some(transform_usage) = config.transform_usage {
+            self.transform_usage = transform_usage;
+        }
+        if let Some(generators) = config.generators {
+            self.generators = generators;
+        }
+        if let Some(hooks_file) = config.hooks_file {
+            self.hooks_file = hooks_file;
+        }
+        if let Some(components_file) = config.components_file {
+            self.components_file = components_file;
+        }
+        if let Some(states_file) = config.states_file {
+            self.states_file = states_file;
+        }
+        if let Some(schema_file) = config.schema_file {
+            self.schema_file = schema_file;
+        }
+
+        Ok(self)
+    }
+
+    /// The root C# namespace, submodules are appended to it. Defaults to `unrust.userland`.
+    pub fn namespace(mut self, namespace: impl Into<String>) -> Codegen {
+        self.namespace = namespace.into();
+        self
+    }
+
+    /// Appended to the type name for the MonoBehaviour and its file. Defaults to `Authoring`.
+    pub fn authoring_suffix(mut self, suffix: impl Into<String>) -> Codegen {
+        self.authoring_suffix = suffix.into();
+        self
+    }
+
+    pub fn layout(mut self, layout: OutputLayout) -> Codegen {
+        self.layout = layout;
+        self
+    }
+
+    /// Used by the component Bakers. Defaults to [`TransformUsage::Dynamic`].
+    pub fn default_transform_usage(mut self, transform_usage: TransformUsage) -> Codegen {
+        self.transform_usage = transform_usage;
+        self
+    }
+
+    pub fn generators(mut self, generators: impl IntoIterator<Item = Generator>) -> Codegen {
+        self.generators = generators.into_iter().collect();
+        self
+    }
+
+    pub fn hooks_file(mut self, name: impl Into<String>) -> Codegen {
+        self.hooks_file = name.into();
+        self
+    }
+
+    pub fn components_file(mut self, name: impl Into<String>) -> Codegen {
+        self.components_file = name.into();
+        self
+    }
+
+    pub fn states_file(mut self, name: impl Into<String>) -> Codegen {
+        self.states_file = name.into();
+        self
+    }
+
+    /// The json manifest of all bridged types: names, wire ids, fields, sizes and offsets.
+    pub fn schema_file(mut self, name: impl Into<String>) -> Codegen {
+        self.schema_file = name.into();
+        self
+    }
+
+    /// Also runs `backend`, writing its files to `output`.
+    pub fn backend(
+        mut self,
+        backend: impl CodegenBackend + 'static,
+        output: impl Into<PathBuf>,
+    ) -> Codegen {
+        self.backends.push(BackendOutput {
+            backend: Arc::new(backend),
+            output: output.into(),
+        });
+        self
+    }
+
+    /// Runs only `backend`, for projects that do not use unity as their editor.
+    pub fn only_backend(
+        mut self,
+        backend: impl CodegenBackend + 'static,
+        output: impl Into<PathBuf>,
+    ) -> Codegen {
+        self.backends.clear();
+        self.backend(backend, output)
+    }
+
+    /// Compare with the files on disk instead of writing them, see [`crate::check_csharp`].
+    pub fn check(mut self, check: bool) -> Codegen {
+        self.check = check;
+        self
+    }
+
+    pub(crate) fn runs(&self, generator: Generator) -> bool {
+        self.generators.contains(&generator)
+    }
+
+    /// The C# namespace for types in the rust module at `module_path`.
+    pub(crate) fn namespace_for(&self, module_path: &[String]) -> String {
+        std::iter::once(self.namespace.as_str())
+            .chain(module_path.iter().map(String::as_str))
+            .collect::<Vec<_>>()
+            .join(".")
+    }
+
+    /// The output file (relative to the output folder) for a per type authoring file.
+    pub(crate) fn authoring_file(&self, module_path: &[String], name: &str) -> String {
+        self.type_file(module_path, &format!("{name}{}", self.authoring_suffix))
+    }
+
+    /// The output file for a per type file without a MonoBehaviour, eg: a `#[unity_enum]`.
+    pub(crate) fn type_file(&self, module_path: &[String], name: &str) -> String {
+        let file = format!("{name}.cs");
+        match self.layout {
+            OutputLayout::Flat => file,
+            OutputLayout::Modules => module_path
+                .iter()
+                .map(String::as_str)
+                .chain(std::iter::once(file.as_str()))
+                .collect::<Vec<_>>()
+                .join("/"),
+        }
+    }
+}
+
+#[derive(Clone)]
+pub(crate) struct BackendOutput {
+    pub backend: Arc<dyn CodegenBackend>,
+    pub output: PathBuf,
+}
+
+impl std::fmt::Debug for BackendOutput {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("BackendOutput")
+            .field("backend", &self.backend.name())
+            .field("output", &self.output)
+            .finish()
+    }
+}
+
+#[derive(Deserialize)]
+struct ProjectConfig {
+    codegen: Option<CodegenConfig>,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct CodegenConfig {
+    root: Option<PathBuf>,
+    output: Option<PathBuf>,
+    /// Also writes `unrust.h` to this folder.
+    c_header: Option<PathBuf>,
+    namespace: Option<String>,
+    authoring_suffix: Option<String>,
+    layout: Option<OutputLayout>,
+    transform_usage: Option<TransformUsage>,
+    generators: Option<Vec<Generator>>,
+    hooks_file: Option<String>,
+    components_file: Option<String>,
+    states_file: Option<String>,
+    schema_file: Option<String>,
+}
@@ -0,0 +1,183 @@
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+use syn::spanned::Spanned;
+
+/// A single problem found while reading the rust types, pointing at the offending source.
+#[derive(Debug, Clone)]
+pub struct Diagnostic {
+    pub file: PathBuf,
+    pub line: usize,
+    pub column: usize,
+    pub message: String,
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}:{}:{}: {}",
+            self.file.display(),
+            self.line,
+            self.column,
+            self.message
+        )
+    }
+}
+
+/// Every problem found during a codegen run. Returned instead of stopping at the first one,
+/// so a single `cargo build` shows everything that needs fixing.
+#[derive(Debug)]
+pub struct CodegenError {
+    pub diagnostics: Vec<Diagnostic>,
+}
+
+impl fmt::Display for CodegenError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "unrust codegen found {} error(s):",
+            self.diagnostics.len()
+        )?;
+        for diagnostic in &self.diagnostics {
+            writeln!(f, "  {diagnostic}")?;
+        }
+
+        Ok(())
+    }
+}
+
+impl std::error::Error for CodegenError {}
+
+#[derive(Default)]
+pub(crate) struct Diagnostics {
+    diagnostics: Vec<Diagnostic>,
+}
+
+impl Diagnostics {
+    pub(crate) fn error(
+        &mut self,
+        file: &Path,
+        spanned: &impl Spanned,
+        message: impl Into<String>,
+    ) {
+        let start = spanned.span().start();
+        self.diagnostics.push(Diagnostic {
+            file: file.to_path_buf(),
+            line: start.line,
+            column: start.column + 1,
+            message: message.into(),
+        });
+    }
+
+    pub(crate) fn syn_error(&mut self, file: &Path, err: syn::Error) {
+        for err in err {
+            self.error(file, &err.span(), err.to_string());
+        }
+    }
+
+    pub(crate) fn finish(self) -> Result<(), CodegenError> {
+        if self.diagnostics.is_empty() {
+            return Ok(());
+        }
+
+        Err(self.into_error())
+    }
+
+    pub(crate) fn into_error(self) -> CodegenError {
+        // print them for cargo as well, build script errors otherwise only show up as a single blob
+        if std::env::var_os("OUT_DIR").is_some() {
+            for diagnostic in &self.diagnostics {
+                println!("cargo:warning={diagnostic}");
+            }
+        }
+
+        CodegenError {
+            diagnostics: self.diagnostics,
+        }
+    }
+}
+
+const CSHARP_KEYWORDS: &[&str] = &[
+    "abstract",
+    "as",
+    "base",
+    "bool",
+    "break",
+    "byte",
+    "case",
+    "catch",
+    "char",
+    "checked",
+    "class",
+    "const",
+    "continue",
+    "decimal",
+    "default",
+    "delegate",
+    "do",
+    "double",
+    "else",
+    "enum",
+    "event",
+    "explicit",
+    "extern",
+    "false",
+    "finally",
+    "fixed",
+    "float",
+    "for",
+    "foreach",
+    "goto",
+    "if",
+    "implicit",
+    "in",
+    "int",
+    "interface",
+    "internal",
+    "is",
+    "lock",
+    "long",
+    "namespace",
+    "new",
+    "null",
+    "object",
+    "operator",
+    "out",
+    "override",
+    "params",
+    "private",
+    "protected",
+    "public",
+    "readonly",
+    "ref",
+    "return",
+    "sbyte",
+    "sealed",
+    "short",
+    "sizeof",
+    "stackalloc",
+    "static",
+    "string",
+    "struct",
+    "switch",
+    "this",
+    "throw",
+    "true",
+    "try",
+    "typeof",
+    "uint",
+    "ulong",
+    "unchecked",
+    "unsafe",
+    "ushort",
+    "using",
+    "virtual",
+    "void",
+    "volatile",
+    "while",
+];
+
+pub(crate) fn is_csharp_keyword(name: &str) -> bool {
+    CSHARP_KEYWORDS.contains(&name)
+}
@@ -0,0 +1,3 @@
+mod deeper;
+
+pub struct Flat;
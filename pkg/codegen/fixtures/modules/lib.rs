@@ -0,0 +1,24 @@
+mod flat;
+mod nested;
+
+#[path = "renamed/custom.rs"]
+mod pathed;
+
+mod inline {
+    pub struct Inline;
+
+    mod child;
+
+    #[path = "elsewhere"]
+    mod moved {
+        mod leaf;
+    }
+}
+
+#[cfg(feature = "gated")]
+mod gated;
+
+#[cfg(target_os = "none")]
+mod missing;
+
+pub struct Root;
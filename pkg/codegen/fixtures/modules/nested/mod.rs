@@ -0,0 +1,3 @@
+mod sibling;
+
+pub struct Nested;
@@ -0,0 +1,3 @@
+mod beside;
+
+pub struct Pathed;
@@ -0,0 +1,37 @@
+#[unity_authoring]
+pub struct Speed {
+    pub value: f32,
+}
+
+#[unity_authoring]
+pub struct Player;
+
+#[unity_resource]
+pub struct Difficulty {
+    pub level: u8,
+}
+
+#[bevy_state]
+pub enum GameState {
+    Menu,
+    Playing,
+}
+
+#[unity_prefab]
+pub enum Enemies {
+    Grunt,
+    Boss,
+}
+
+#[unity_prefab]
+pub enum Pickups {
+    Coin,
+}
+
+#[unrust_setup(
+    components = [Player, Speed],
+    resources = [Difficulty],
+    states = [GameState],
+    prefabs = [Enemies, Pickups]
+)]
+pub fn setup(_app: &mut App) {}
@@ -0,0 +1,443 @@
+use std::collections::HashMap;
+use std::path::Path;
+use syn::ext::IdentExt;
+use syn::spanned::Spanned;
+
+use crate::diagnostics::Diagnostics;
+use crate::model::FieldType;
+
+/// How a field shows up in the inspector, from its attributes and doc comment. The
+/// `#[unity_authoring]` and `#[unity_struct]` macros strip the attributes again.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Inspector {
+    /// `#[header("..")]`, shown above the field.
+    pub header: Option<String>,
+    /// `#[tooltip("..")]`, or else the doc comment.
+    pub tooltip: Option<String>,
+    /// `#[range(min, max)]` on numbers, shown as a slider.
+    pub range: Option<(String, String)>,
+    /// `#[default = ..]`, or else the field's value in the struct's `impl Default`.
+    pub default: Option<DefaultValue>,
+    /// `#[validate(..)]` rules, warned about in `OnValidate`.
+    pub validation: Vec<Validation>,
+}
+
+/// A default value the authoring MonoBehaviour can start with.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DefaultValue {
+    /// A number as written, without `_`s or suffix.
+    Number(String),
+    Bool(bool),
+    Char(char),
+    String(String),
+    /// The components of a glam vector or quaternion, or rgba of a color.
+    Floats(Vec<String>),
+    /// A `#[unity_enum]` variant.
+    Variant(String),
+}
+
+/// A `#[validate(..)]` rule the editor can check, applying to each element of arrays and Vecs.
+/// `with = path` functions are only called in rust.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Validation {
+    Min(String),
+    Max(String),
+    NonZero,
+    NonEmpty,
+}
+
+/// Reads the inspector attributes of `field`. `fallback` is the field's value in the struct's
+/// `impl Default`, which is skipped if the inspector can't show it.
+pub(crate) fn field_inspector(
+    file: &Path,
+    field: &syn::Field,
+    ty: &FieldType,
+    fallback: Option<&syn::Expr>,
+    monobehaviour: bool,
+    diagnostics: &mut Diagnostics,
+) -> Inspector {
+    let mut inspector = Inspector::default();
+    let mut docs = vec![];
+
+    for attr in &field.attrs {
+        let result = if attr.path().is_ident("header") {
+            attr.parse_args::<syn::LitStr>()
+                .map(|text| inspector.header = Some(text.value()))
+        } else if attr.path().is_ident("tooltip") {
+            attr.parse_args::<syn::LitStr>()
+                .map(|text| inspector.tooltip = Some(text.value()))
+        } else if attr.path().is_ident("range") {
+            parse_range(attr, ty).map(|range| inspector.range = Some(range))
+        } else if attr.path().is_ident("default") {
+            parse_default(attr, ty, monobehaviour).map(|default| inspector.default = Some(default))
+        } else if attr.path().is_ident("validate") {
+            parse_validate(attr, ty, monobehaviour).map(|rules| inspector.validation.extend(rules))
+        } else if attr.path().is_ident("doc") {
+            if let syn::Meta::NameValue(syn::MetaNameValue {
+                value:
+                    syn::Expr::Lit(syn::ExprLit {
+                        lit: syn::Lit::Str(doc),
+                        ..
+                    }),
+                ..
+            }) = &attr.meta
+            {
+                docs.push(doc.value().trim().to_string());
+            }
+            Ok(())
+        } else {
+            Ok(())
+        };
+
+        if let Err(err) = result {
+            diagnostics.syn_error(file, err);
+        }
+    }
+
+    if inspector.tooltip.is_none() && !docs.is_empty() {
+        inspector.tooltip = Some(docs.join(" ").trim().to_string());
+    }
+    if inspector.default.is_none() && monobehaviour {
+        inspector.default = fallback.and_then(|expr| default_value(expr, ty));
+    }
+
+    inspector
+}
+
+/// The fields set by the struct literal that `impl Default for #name` in `items` returns.
+pub(crate) fn impl_default_fields<'a>(
+    items: &'a [syn::Item],
+    name: &str,
+) -> HashMap<String, &'a syn::Expr> {
+    let body = items.iter().find_map(|item| {
+        let syn::Item::Impl(item) = item else {
+            return None;
+        };
+        let (_, trait_path, _) = item.trait_.as_ref()?;
+        if trait_path.segments.last()?.ident != "Default" || last_ident(&item.self_ty)? != name {
+            return None;
+        }
+
+        item.items.iter().find_map(|item| match item {
+            syn::ImplItem::Fn(f) if f.sig.ident == "default" => Some(&f.block),
+            _ => None,
+        })
+    });
+
+    let literal = body.and_then(|block| match block.stmts.last()? {
+        syn::Stmt::Expr(syn::Expr::Struct(literal), None) => Some(literal),
+        syn::Stmt::Expr(syn::Expr::Return(ret), _) => match ret.expr.as_deref()? {
+            syn::Expr::Struct(literal) => Some(literal),
+            _ => None,
+        },
+        _ => None,
+    });
+
+    literal
+        .into_iter()
+        .flat_map(|literal| &literal.fields)
+        .filter_map(|field| match &field.member {
+            syn::Member::Named(ident) => Some((ident.unraw().to_string(), &field.expr)),
+            syn::Member::Unnamed(_) => None,
+        })
+        .collect()
+}
+
+fn parse_range(attr: &syn::Attribute, ty: &FieldType) -> syn::Result<(String, String)> {
+    let element = match ty {
+        FieldType::Array(ty, _) | FieldType::Vec(ty) => ty,
+        _ => ty,
+    };
+    if !is_number(element) {
+        return Err(syn::Error::new(
+            attr.span(),
+            format!(
+                "#[range] only works on numbers, not `{}`",
+                element.rust_name()
+            ),
+        ));
+    }
+
+    let args = attr.parse_args_with(
+        syn::punctuated::Punctuated::<syn::Expr, syn::Token![,]>::parse_separated_nonempty,
+    )?;
+    let bounds = args.iter().map(number).collect::<Option<Vec<_>>>();
+    match bounds.as_deref() {
+        Some([min, max]) => Ok((min.clone(), max.clone())),
+        _ => Err(syn::Error::new(
+            args.span(),
+            "expected #[range(min, max)] with two number literals",
+        )),
+    }
+}
+
+fn parse_default(
+    attr: &syn::Attribute,
+    ty: &FieldType,
+    allow_default: bool,
+) -> syn::Result<DefaultValue> {
+    let syn::Meta::NameValue(meta) = &attr.meta else {
+        return Err(syn::Error::new(attr.span(), "expected #[default = value]"));
+    };
+    // serializable structs can't have field initializers in unity's C#
+    if !allow_default {
+        return Err(syn::Error::new(
+            attr.span(),
+            "#[default] is only supported on #[unity_authoring] structs",
+        ));
+    }
+
+    default_value(&meta.value, ty).ok_or_else(|| {
+        syn::Error::new(
+            meta.value.span(),
+            format!(
+                "this default can not be shown in the inspector for a `{}`",
+                ty.rust_name()
+            ),
+        )
+    })
+}
+
+fn parse_validate(
+    attr: &syn::Attribute,
+    ty: &FieldType,
+    allow_validate: bool,
+) -> syn::Result<Vec<Validation>> {
+    // serializable structs have no OnValidate, and nested values are not checked on spawn
+    if !allow_validate {
+        return Err(syn::Error::new(
+            attr.span(),
+            "#[validate] is only supported on #[unity_authoring] structs",
+        ));
+    }
+
+    let element = match ty {
+        FieldType::Array(ty, _) | FieldType::Vec(ty) => ty,
+        _ => ty,
+    };
+    let unsupported = |meta: &syn::meta::ParseNestedMeta, ty: &FieldType| {
+        let rule = meta
+            .path
+            .get_ident()
+            .map(ToString::to_string)
+            .unwrap_or_default();
+        meta.error(format!(
+            "#[validate({rule})] does not work on `{}`",
+            ty.rust_name()
+        ))
+    };
+
+    let mut rules = vec![];
+    attr.parse_nested_meta(|meta| {
+        if meta.path.is_ident("min") || meta.path.is_ident("max") {
+            if !is_number(element) {
+                return Err(unsupported(&meta, element));
+            }
+            let expr: syn::Expr = meta.value()?.parse()?;
+            let Some(DefaultValue::Number(bound)) = default_value(&expr, element) else {
+                return Err(syn::Error::new(
+                    expr.span(),
+                    format!("expected a `{}` literal", element.rust_name()),
+                ));
+            };
+            rules.push(match meta.path.is_ident("min") {
+                true => Validation::Min(bound),
+                false => Validation::Max(bound),
+            });
+        } else if meta.path.is_ident("non_zero") {
+            let vector = matches!(element, FieldType::Vec2 | FieldType::Vec3 | FieldType::Vec4);
+            if !is_number(element) && !vector {
+                return Err(unsupported(&meta, element));
+            }
+            rules.push(Validation::NonZero);
+        } else if meta.path.is_ident("non_empty") {
+            if !matches!(ty, FieldType::String | FieldType::Vec(_)) {
+                return Err(unsupported(&meta, ty));
+            }
+            rules.push(Validation::NonEmpty);
+        } else if meta.path.is_ident("with") {
+            meta.value()?.parse::<syn::Path>()?;
+        } else {
+            return Err(meta.error("expected `min`, `max`, `non_zero`, `non_empty` or `with`"));
+        }
+        Ok(())
+    })?;
+
+    Ok(rules)
+}
+
+/// Converts the literals and constructors the inspector can start with, eg: `1.5`,
+/// `"name".to_string()`, `Vec3::new(0.0, 1.0, 0.0)` or `Team::Blue`.
+fn default_value(expr: &syn::Expr, ty: &FieldType) -> Option<DefaultValue> {
+    let value = match ty {
+        FieldType::Bool => match expr {
+            syn::Expr::Lit(syn::ExprLit {
+                lit: syn::Lit::Bool(value),
+                ..
+            }) => DefaultValue::Bool(value.value),
+            _ => return None,
+        },
+        FieldType::Char => match expr {
+            // C# chars are utf-16 units
+            syn::Expr::Lit(syn::ExprLit {
+                lit: syn::Lit::Char(value),
+                ..
+            }) if value.value().len_utf16() == 1 => DefaultValue::Char(value.value()),
+            _ => return None,
+        },
+        FieldType::F32 | FieldType::F64 => DefaultValue::Number(number(expr)?),
+        ty if is_number(ty) => {
+            let value = number(expr)?;
+            let unsigned = matches!(
+                ty,
+                FieldType::U8 | FieldType::U16 | FieldType::U32 | FieldType::U64
+            );
+            if value.contains(['.', 'e', 'E']) || (unsigned && value.starts_with('-')) {
+                return None;
+            }
+            DefaultValue::Number(value)
+        }
+        FieldType::String => DefaultValue::String(string(expr)?),
+        FieldType::Vec2 => DefaultValue::Floats(floats(expr, "Vec2", 2)?),
+        FieldType::Vec3 => DefaultValue::Floats(floats(expr, "Vec3", 3)?),
+        FieldType::Vec4 => DefaultValue::Floats(floats(expr, "Vec4", 4)?),
+        FieldType::Quat => match path_ident(expr)?.as_str() {
+            "IDENTITY" => DefaultValue::Floats(["0", "0", "0", "1"].map(String::from).to_vec()),
+            _ => return None,
+        },
+        FieldType::Color => DefaultValue::Floats(color(expr)?),
+        FieldType::Enum(item) => {
+            let variant = path_ident(expr)?;
+            if !item.variants.contains(&variant) {
+                return None;
+            }
+            DefaultValue::Variant(variant)
+        }
+        _ => return None,
+    };
+
+    Some(value)
+}
+
+fn is_number(ty: &FieldType) -> bool {
+    matches!(
+        ty,
+        FieldType::U8
+            | FieldType::I8
+            | FieldType::U16
+            | FieldType::I16
+            | FieldType::U32
+            | FieldType::I32
+            | FieldType::U64
+            | FieldType::I64
+            | FieldType::F32
+            | FieldType::F64
+    )
+}
+
+/// A number literal, optionally negated.
+fn number(expr: &syn::Expr) -> Option<String> {
+    match expr {
+        syn::Expr::Lit(syn::ExprLit { lit, .. }) => match lit {
+            syn::Lit::Int(value) => Some(value.base10_digits().to_string()),
+            syn::Lit::Float(value) => Some(value.base10_digits().to_string()),
+            _ => None,
+        },
+        syn::Expr::Unary(syn::ExprUnary {
+            op: syn::UnOp::Neg(_),
+            expr,
+            ..
+        }) => Some(format!("-{}", number(expr)?)),
+        syn::Expr::Paren(paren) => number(&paren.expr),
+        _ => None,
+    }
+}
+
+/// `"text"`, `String::from("text")`, `String::new()` or `"text".to_string()` and friends.
+fn string(expr: &syn::Expr) -> Option<String> {
+    match expr {
+        syn::Expr::Lit(syn::ExprLit {
+            lit: syn::Lit::Str(value),
+            ..
+        }) => Some(value.value()),
+        syn::Expr::Call(call) => match (path_ident(&call.func)?.as_str(), call.args.first()) {
+            ("from", Some(arg)) if call.args.len() == 1 => string(arg),
+            ("new", None) => Some(String::new()),
+            _ => None,
+        },
+        syn::Expr::MethodCall(call)
+            if call.args.is_empty()
+                && ["to_string", "to_owned", "into"]
+                    .iter()
+                    .any(|method| call.method == method) =>
+        {
+            string(&call.receiver)
+        }
+        _ => None,
+    }
+}
+
+/// `Vec3::new(..)`, `Vec3::splat(..)`, `Vec3::ZERO` or `Vec3::ONE`.
+fn floats(expr: &syn::Expr, ty: &str, len: usize) -> Option<Vec<String>> {
+    match expr {
+        syn::Expr::Call(call) => {
+            let syn::Expr::Path(func) = call.func.as_ref() else {
+                return None;
+            };
+            let segments = &func.path.segments;
+            if segments.len() < 2 || segments[segments.len() - 2].ident != ty {
+                return None;
+            }
+
+            let args = call.args.iter().map(number).collect::<Option<Vec<_>>>()?;
+            match (segments.last()?.ident.to_string().as_str(), &args[..]) {
+                ("new", args) if args.len() == len => Some(args.to_vec()),
+                ("splat", [value]) => Some(vec![value.clone(); len]),
+                _ => None,
+            }
+        }
+        _ => match path_ident(expr)?.as_str() {
+            "ZERO" => Some(vec!["0".to_string(); len]),
+            "ONE" => Some(vec!["1".to_string(); len]),
+            _ => None,
+        },
+    }
+}
+
+/// `Color::rgb(..)`, `Color::rgba(..)`, `Color::WHITE` or `Color::BLACK`, as srgb rgba.
+fn color(expr: &syn::Expr) -> Option<Vec<String>> {
+    let opaque = |rgb: [&str; 3]| rgb.into_iter().chain(["1"]).map(String::from).collect();
+    match expr {
+        syn::Expr::Call(call) => {
+            let mut args = call.args.iter().map(number).collect::<Option<Vec<_>>>()?;
+            match (path_ident(&call.func)?.as_str(), args.len()) {
+                ("rgb", 3) => {
+                    args.push("1".to_string());
+                    Some(args)
+                }
+                ("rgba", 4) => Some(args),
+                _ => None,
+            }
+        }
+        _ => match path_ident(expr)?.as_str() {
+            "WHITE" => Some(opaque(["1", "1", "1"])),
+            "BLACK" => Some(opaque(["0", "0", "0"])),
+            _ => None,
+        },
+    }
+}
+
+/// The last segment of a path expression, eg: `Blue` for `Team::Blue`.
+fn path_ident(expr: &syn::Expr) -> Option<String> {
+    match expr {
+        syn::Expr::Path(path) => Some(path.path.segments.last()?.ident.unraw().to_string()),
+        _ => None,
+    }
+}
+
+fn last_ident(ty: &syn::Type) -> Option<String> {
+    match ty {
+        syn::Type::Path(path) => Some(path.path.segments.last()?.ident.unraw().to_string()),
+        _ => None,
+    }
+}
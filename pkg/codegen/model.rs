@@ -0,0 +1,1174 @@
+use anyhow::Result;
+use std::collections::HashMap;
+use std::path::Path;
+use syn::ext::IdentExt;
+use syn::spanned::Spanned;
+
+use crate::diagnostics::{is_csharp_keyword, Diagnostics};
+use crate::inspector::{self, Inspector};
+use crate::modules::{self, SourceModule};
+use crate::{Codegen, Generator, TransformUsage, CHECK_ENV};
+
+/// Every annotated type found in the crate, in discovery order, which is also their wire order.
+/// This is what a [`crate::CodegenBackend`] generates its output from.
+#[derive(Debug, Clone, Default)]
+pub struct CodegenModel {
+    pub components: Vec<AuthoringStruct>,
+    /// `#[unity_resource]` structs, sent like components after them and inserted as bevy resources.
+    pub resources: Vec<AuthoringStruct>,
+    /// `#[unity_config]` structs, edited as ScriptableObjects and exported to a file.
+    pub configs: Vec<AuthoringStruct>,
+    /// `#[unity_enum]` enums, only found when components are generated since they are used as fields.
+    pub enums: Vec<AuthoringEnum>,
+    /// `#[unity_struct]` structs, ordered so a struct comes after the ones it contains.
+    pub structs: Vec<AuthoringStruct>,
+    pub states: Vec<AuthoringEnum>,
+    pub prefabs: Vec<AuthoringEnum>,
+}
+
+/// A `#[unity_authoring]`, `#[unity_resource]`, `#[unity_config]` or `#[unity_struct]` struct
+/// found in the crate.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AuthoringStruct {
+    pub name: String,
+    /// Module path relative to the crate root, empty for types in the root itself.
+    pub module: Vec<String>,
+    pub fields: Vec<AuthoringField>,
+    /// Always the defaults for `#[unity_struct]`s.
+    pub options: AuthoringOptions,
+}
+
+/// The `#[unity_authoring(transform = "..", requires = [..], menu = "..")]` options of a component.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct AuthoringOptions {
+    /// Replaces [`crate::Codegen::default_transform_usage`] in the component's Baker.
+    pub transform: Option<TransformUsage>,
+    /// Components unity adds along with this one, with `[RequireComponent]`.
+    pub requires: Vec<RequiredComponent>,
+    /// Where the component is listed in unity's Add Component menu.
+    pub menu: Option<String>,
+}
+
+/// Another `#[unity_authoring]` struct, by name and module path.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RequiredComponent {
+    pub name: String,
+    pub module: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AuthoringField {
+    pub name: String,
+    pub ty: FieldType,
+    pub inspector: Inspector,
+}
+
+/// The field types that can cross the boundary, laid out as `#[repr(C)]`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FieldType {
+    Bool,
+    U8,
+    I8,
+    U16,
+    I16,
+    Char,
+    F32,
+    F64,
+    I32,
+    I64,
+    U32,
+    U64,
+    Vec2,
+    Vec3,
+    Vec4,
+    Quat,
+    Mat4,
+    /// Sent as srgb `[f32; 4]`, see `UnityComponent::Wire`.
+    Color,
+    /// Utf-8 in a unity `FixedString64Bytes`, see `unrust::UnityString`.
+    String,
+    /// A `UnityEntityRef`, a GameObject baked to its entity and sent as a `UnityEntity`.
+    EntityRef,
+    /// A bevy `AssetPath`, the asset's path relative to `Assets`, see `unrust::UnityAssetPath`.
+    AssetPath,
+    /// A `Handle<T>` of the named asset type, sent as its path and loaded on spawn.
+    Handle(String),
+    /// A `UnityCurve`, an `AnimationCurve` baked to evenly spaced samples.
+    Curve,
+    /// A `UnityGradient`, a `Gradient` baked to evenly spaced srgb colors.
+    Gradient,
+    /// A `#[unity_enum]`, sent as the `u8` index of its variant.
+    Enum(AuthoringEnum),
+    /// A `#[unity_struct]`, laid out inline.
+    Struct(AuthoringStruct),
+    /// `[T; N]` of numbers, `bool`, `char` or `#[unity_enum]`s, which C# can hold in a fixed buffer.
+    Array(Box<FieldType>, usize),
+    /// A `Vec<T>` on a component, baked into a unity `DynamicBuffer` and sent as a pointer
+    /// and length, see `unrust::UnityBuffer`.
+    Vec(Box<FieldType>),
+}
+
+impl FieldType {
+    pub fn rust_name(&self) -> String {
+        let name = match self {
+            FieldType::Bool => "bool",
+            FieldType::U8 => "u8",
+            FieldType::I8 => "i8",
+            FieldType::U16 => "u16",
+            FieldType::I16 => "i16",
+            FieldType::Char => "char",
+            FieldType::F32 => "f32",
+            FieldType::F64 => "f64",
+            FieldType::I32 => "i32",
+            FieldType::I64 => "i64",
+            FieldType::U32 => "u32",
+            FieldType::U64 => "u64",
+            FieldType::Vec2 => "Vec2",
+            FieldType::Vec3 => "Vec3",
+            FieldType::Vec4 => "Vec4",
+            FieldType::Quat => "Quat",
+            FieldType::Mat4 => "Mat4",
+            FieldType::Color => "Color",
+            FieldType::String => "String",
+            FieldType::EntityRef => "UnityEntityRef",
+            FieldType::AssetPath => "AssetPath",
+            FieldType::Curve => "UnityCurve",
+            FieldType::Gradient => "UnityGradient",
+            FieldType::Handle(asset) => return format!("Handle<{asset}>"),
+            FieldType::Enum(item) => &item.name,
+            FieldType::Struct(item) => &item.name,
+            FieldType::Array(ty, len) => return format!("[{}; {len}]", ty.rust_name()),
+            FieldType::Vec(ty) => return format!("Vec<{}>", ty.rust_name()),
+        };
+
+        name.to_string()
+    }
+
+    /// Size in bytes on the wire.
+    pub fn size(&self) -> usize {
+        match self {
+            FieldType::Struct(item) => item.layout().size,
+            FieldType::Array(ty, len) => ty.size() * len,
+            _ => self.scalar_size(),
+        }
+    }
+
+    /// Alignment in bytes on the wire. The glam vectors are simd aligned, see `unrust::UnityComponent`.
+    pub fn align(&self) -> usize {
+        match self {
+            FieldType::Struct(item) => item.layout().align,
+            FieldType::Array(ty, _) => ty.align(),
+            _ => self.scalar_align(),
+        }
+    }
+
+    /// Whether `[Self; N]` can be a field, see [`FieldType::Array`].
+    pub fn fits_fixed_buffer(&self) -> bool {
+        !matches!(
+            self,
+            FieldType::Vec2
+                | FieldType::Vec3
+                | FieldType::Vec4
+                | FieldType::Quat
+                | FieldType::Mat4
+                | FieldType::Color
+                | FieldType::String
+                | FieldType::EntityRef
+                | FieldType::AssetPath
+                | FieldType::Handle(_)
+                | FieldType::Curve
+                | FieldType::Gradient
+                | FieldType::Struct(_)
+                | FieldType::Array(..)
+                | FieldType::Vec(_)
+        )
+    }
+
+    const fn scalar_size(&self) -> usize {
+        match self {
+            FieldType::Bool | FieldType::U8 | FieldType::I8 | FieldType::Enum(_) => 1,
+            FieldType::U16 | FieldType::I16 => 2,
+            FieldType::Char | FieldType::F32 | FieldType::I32 | FieldType::U32 => 4,
+            FieldType::F64
+            | FieldType::I64
+            | FieldType::U64
+            | FieldType::Vec2
+            | FieldType::EntityRef => 8,
+            FieldType::Vec3 => 12,
+            FieldType::Vec4 | FieldType::Quat | FieldType::Color => 16,
+            FieldType::Mat4 | FieldType::String => 64,
+            FieldType::AssetPath | FieldType::Handle(_) => 128,
+            // the range of the keys and 32 samples, 16 rgba samples, see `unrust::UnityCurve`
+            FieldType::Curve => 136,
+            FieldType::Gradient => 256,
+            // pointer and length, unity only runs on 64 bit
+            FieldType::Vec(_) => 16,
+            FieldType::Struct(_) | FieldType::Array(..) => unreachable!(),
+        }
+    }
+
+    const fn scalar_align(&self) -> usize {
+        match self {
+            FieldType::Vec2
+            | FieldType::Vec3
+            | FieldType::Color
+            | FieldType::EntityRef
+            | FieldType::Curve
+            | FieldType::Gradient => 4,
+            FieldType::Vec4 | FieldType::Quat | FieldType::Mat4 => 16,
+            FieldType::Vec(_) => 8,
+            FieldType::String | FieldType::AssetPath | FieldType::Handle(_) => 2,
+            _ => self.scalar_size(),
+        }
+    }
+}
+
+// the tables above have to agree with rustc, the C# side is checked when the hooks initialize
+// and the glam types are checked by the unrust crate
+const _: () = {
+    use std::mem::{align_of, size_of};
+
+    macro_rules! assert_layout {
+        ($($variant:ident => $ty:ty),*) => {
+            $(
+                assert!(FieldType::$variant.scalar_size() == size_of::<$ty>());
+                assert!(FieldType::$variant.scalar_align() == align_of::<$ty>());
+            )*
+        };
+    }
+
+    assert_layout!(
+        Bool => bool, U8 => u8, I8 => i8, U16 => u16, I16 => i16, Char => char,
+        F32 => f32, F64 => f64, I32 => i32, I64 => i64, U32 => u32, U64 => u64
+    );
+};
+
+/// The `#[repr(C)]` layout of a struct: every field aligned, padded to the largest alignment.
+#[derive(Debug, Clone)]
+pub struct Layout {
+    pub size: usize,
+    pub align: usize,
+    pub offsets: Vec<usize>,
+}
+
+impl AuthoringStruct {
+    /// A component without fields, which is only a marker. Tags are sent without a value,
+    /// C# makes empty structs 1 byte while rust makes them 0.
+    pub fn is_tag(&self) -> bool {
+        self.fields.is_empty()
+    }
+
+    pub fn layout(&self) -> Layout {
+        let mut offset = 0usize;
+        let mut align = 1usize;
+        let offsets = self
+            .fields
+            .iter()
+            .map(|field| {
+                offset = offset.next_multiple_of(field.ty.align());
+                align = align.max(field.ty.align());
+
+                let field_offset = offset;
+                offset += field.ty.size();
+                field_offset
+            })
+            .collect();
+
+        Layout {
+            size: offset.next_multiple_of(align),
+            align,
+            offsets,
+        }
+    }
+}
+
+impl CodegenModel {
+    /// The `#[repr(C)]` layout of the `CustomData` unity sends components and resources in,
+    /// a `u8` wire id at offset 0 followed by a union of their values at `offsets[1]`. When
+    /// there are only tags there is no union, the wire id is all that is sent.
+    pub fn custom_data_layout(&self) -> Layout {
+        let values = self
+            .components
+            .iter()
+            .chain(&self.resources)
+            .filter(|item| !item.is_tag())
+            .map(AuthoringStruct::layout);
+        let Some(align) = values.clone().map(|layout| layout.align).max() else {
+            return Layout {
+                size: 1,
+                align: 1,
+                offsets: vec![0],
+            };
+        };
+        let size = values
+            .map(|layout| layout.size)
+            .max()
+            .unwrap_or(0)
+            .next_multiple_of(align);
+
+        Layout {
+            size: align + size,
+            align,
+            offsets: vec![0, align],
+        }
+    }
+}
+
+/// A `#[bevy_state]`, `#[unity_prefab]` or `#[unity_enum]` enum found in the crate.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AuthoringEnum {
+    pub name: String,
+    /// Module path relative to the crate root, empty for types in the root itself.
+    pub module: Vec<String>,
+    pub variants: Vec<String>,
+}
+
+impl CodegenModel {
+    /// Walks the crate from `config.root` and collects every type the enabled generators handle.
+    pub(crate) fn discover(config: &Codegen) -> Result<CodegenModel> {
+        let mut diagnostics = Diagnostics::default();
+        let modules = modules::collect_modules(&config.root, &mut diagnostics);
+
+        // inside a build script, rerun only when one of the parsed files or the mode changes
+        if std::env::var_os("OUT_DIR").is_some() {
+            println!("cargo:rerun-if-env-changed={CHECK_ENV}");
+            for module in &modules {
+                println!("cargo:rerun-if-changed={}", module.file.display());
+            }
+        }
+
+        let mut names = HashMap::new();
+        let mut model = CodegenModel::default();
+
+        // the module of every component, which `requires = [..]` can point at before it is mapped
+        let component_modules: HashMap<String, Vec<String>> = modules
+            .iter()
+            .flat_map(|module| {
+                structs_with_attr(module, "unity_authoring")
+                    .chain(structs_with_attr(module, "unity_resource"))
+                    .map(|item| (item.ident.unraw().to_string(), module.path.clone()))
+            })
+            .collect();
+
+        // field types first, a field can use one declared further down the crate
+        if config.runs(Generator::Components) {
+            for module in &modules {
+                model.enums.extend(find_enums_with_attr(
+                    module,
+                    "unity_enum",
+                    &mut names,
+                    &mut diagnostics,
+                ));
+            }
+
+            let nested = modules
+                .iter()
+                .flat_map(|module| {
+                    structs_with_attr(module, "unity_struct").map(move |item| (module, item))
+                })
+                .collect::<Vec<_>>();
+            for index in dependency_order(&nested) {
+                let (module, item) = nested[index];
+                let nested = map_struct(
+                    module,
+                    item,
+                    "unity_struct",
+                    &model,
+                    &mut names,
+                    &mut diagnostics,
+                );
+                model.structs.push(nested);
+            }
+        }
+
+        for module in &modules {
+            if config.runs(Generator::Components) {
+                for expected in ["unity_authoring", "unity_resource"] {
+                    let components = structs_with_attr(module, expected)
+                        .map(|item| {
+                            let mut component = map_struct(
+                                module,
+                                item,
+                                expected,
+                                &model,
+                                &mut names,
+                                &mut diagnostics,
+                            );
+                            component.options = authoring_options(
+                                module,
+                                item,
+                                expected,
+                                &component_modules,
+                                &mut diagnostics,
+                            );
+                            component
+                        })
+                        .collect::<Vec<_>>();
+                    match expected {
+                        "unity_authoring" => model.components.extend(components),
+                        _ => model.resources.extend(components),
+                    }
+                }
+
+                // configs are never sent, so they take no part in the wire order
+                let configs = structs_with_attr(module, "unity_config")
+                    .map(|item| {
+                        map_struct(
+                            module,
+                            item,
+                            "unity_config",
+                            &model,
+                            &mut names,
+                            &mut diagnostics,
+                        )
+                    })
+                    .collect::<Vec<_>>();
+                model.configs.extend(configs);
+            }
+            if config.runs(Generator::States) {
+                model.states.extend(find_enums_with_attr(
+                    module,
+                    "bevy_state",
+                    &mut names,
+                    &mut diagnostics,
+                ));
+            }
+            if config.runs(Generator::Prefabs) {
+                model.prefabs.extend(find_enums_with_attr(
+                    module,
+                    "unity_prefab",
+                    &mut names,
+                    &mut diagnostics,
+                ));
+            }
+        }
+        check_setup_order(&modules, &mut diagnostics);
+        diagnostics.finish()?;
+
+        Ok(model)
+    }
+}
+
+/// The wire ids the C# uses follow the order types are discovered in, while `unrust_setup`
+/// numbers them in the order they are listed. A different order would decode one type as another.
+/// This runs whatever generators are enabled, `unrust_setup` numbers the types either way.
+fn check_setup_order(modules: &[SourceModule], diagnostics: &mut Diagnostics) {
+    let names = |attr: &str| -> Vec<String> {
+        modules
+            .iter()
+            .flat_map(|module| {
+                module.items.iter().filter_map(|item| {
+                    let (attrs, ident) = match item {
+                        syn::Item::Struct(item) => (&item.attrs, &item.ident),
+                        syn::Item::Enum(item) => (&item.attrs, &item.ident),
+                        _ => return None,
+                    };
+                    attrs
+                        .iter()
+                        .any(|a| a.path().is_ident(attr))
+                        .then(|| ident.unraw().to_string())
+                })
+            })
+            .collect()
+    };
+    let expected = [
+        ("components", names("unity_authoring")),
+        ("resources", names("unity_resource")),
+        ("states", names("bevy_state")),
+        // a prefab's index in the list is the id its instances are registered under
+        ("prefabs", names("unity_prefab")),
+    ];
+
+    for module in modules {
+        let setups = module.items.iter().filter_map(|item| match item {
+            syn::Item::Fn(item) => item.attrs.iter().find(|attr| {
+                attr.path()
+                    .segments
+                    .last()
+                    .is_some_and(|segment| segment.ident == "unrust_setup")
+            }),
+            _ => None,
+        });
+
+        for setup in setups {
+            // malformed arguments are reported by the macro itself
+            let Ok(sections) = setup.parse_args_with(
+                syn::punctuated::Punctuated::<syn::MetaNameValue, syn::Token![,]>::parse_terminated,
+            ) else {
+                continue;
+            };
+
+            for (key, expected) in &expected {
+                let listed = sections.iter().find(|section| section.path.is_ident(key));
+                let types: Vec<String> = match listed.map(|section| &section.value) {
+                    Some(syn::Expr::Array(array)) => array
+                        .elems
+                        .iter()
+                        .filter_map(|elem| match elem {
+                            syn::Expr::Path(path) => {
+                                Some(path.path.segments.last()?.ident.unraw().to_string())
+                            }
+                            _ => None,
+                        })
+                        .collect(),
+                    _ => vec![],
+                };
+
+                if &types != expected {
+                    let span = match listed {
+                        Some(section) => section.span(),
+                        None => setup.span(),
+                    };
+                    diagnostics.error(
+                        &module.file,
+                        &span,
+                        format!(
+                            "`{key}` has to list every type in the order the codegen numbers them, which is the order they are found walking the modules: `{key} = [{}]`",
+                            expected.join(", ")
+                        ),
+                    );
+                }
+            }
+        }
+    }
+}
+
+fn structs_with_attr<'a>(
+    module: &'a SourceModule,
+    expected: &'a str,
+) -> impl Iterator<Item = &'a syn::ItemStruct> {
+    module
+        .items
+        .iter()
+        .filter_map(|item| match item {
+            syn::Item::Struct(s) => Some(s),
+            _ => None,
+        })
+        .filter(move |item| item.attrs.iter().any(|attr| attr.path().is_ident(expected)))
+}
+
+/// Indices into `structs` so every struct comes after the structs its fields use. Structs
+/// are matched by name like the field types, a cycle is left for rustc to reject.
+fn dependency_order(structs: &[(&SourceModule, &syn::ItemStruct)]) -> Vec<usize> {
+    fn field_type_name(ty: &syn::Type) -> Option<String> {
+        match ty {
+            syn::Type::Path(path) => Some(path.path.segments.last()?.ident.unraw().to_string()),
+            syn::Type::Array(array) => field_type_name(&array.elem),
+            _ => None,
+        }
+    }
+
+    fn visit(
+        index: usize,
+        structs: &[(&SourceModule, &syn::ItemStruct)],
+        visited: &mut Vec<bool>,
+        order: &mut Vec<usize>,
+    ) {
+        if std::mem::replace(&mut visited[index], true) {
+            return;
+        }
+
+        let (_, item) = structs[index];
+        for field in &item.fields {
+            let Some(name) = field_type_name(&field.ty) else {
+                continue;
+            };
+            if let Some(dependency) = structs.iter().position(|(_, other)| other.ident == name) {
+                visit(dependency, structs, visited, order);
+            }
+        }
+
+        order.push(index);
+    }
+
+    let mut visited = vec![false; structs.len()];
+    let mut order = vec![];
+    for index in 0..structs.len() {
+        visit(index, structs, &mut visited, &mut order);
+    }
+
+    order
+}
+
+fn map_struct(
+    module: &SourceModule,
+    item: &syn::ItemStruct,
+    expected: &str,
+    model: &CodegenModel,
+    names: &mut HashMap<String, String>,
+    diagnostics: &mut Diagnostics,
+) -> AuthoringStruct {
+    let file = module.file.as_path();
+    let struct_name = item.ident.unraw().to_string();
+    check_csharp_name(&item.ident, file, diagnostics);
+    check_unique_name(&item.ident, module, names, diagnostics);
+
+    if !item.generics.params.is_empty() {
+        diagnostics.error(
+            file,
+            &item.generics,
+            format!("`{struct_name}` is generic, which is not supported for #[{expected}] structs"),
+        );
+    }
+
+    // only MonoBehaviours can start with other values than C#'s defaults, or have OnValidate
+    let monobehaviour = expected != "unity_struct";
+    let defaults = inspector::impl_default_fields(&module.items, &struct_name);
+
+    let fields = match &item.fields {
+        syn::Fields::Named(fields) => fields
+            .named
+            .iter()
+            .filter_map(|f| {
+                let ident = f.ident.as_ref()?;
+                let field_name = ident.unraw().to_string();
+                check_csharp_name(ident, file, diagnostics);
+
+                let Some(ty) = map_rust_type(&f.ty, module, model) else {
+                    let wide = matches!(&f.ty, syn::Type::Path(path) if path.path.is_ident("u128") || path.path.is_ident("i128"));
+                    let message = match wide {
+                        true => format!("unsupported type for field `{struct_name}.{field_name}`, unity has no 128 bit integers"),
+                        false => format!("unsupported type for field `{struct_name}.{field_name}`"),
+                    };
+                    diagnostics.error(file, &f.ty, message);
+                    return None;
+                };
+
+                // a DynamicBuffer belongs to an entity, so it can't be inside another struct,
+                // `#[unity_struct]`s are Copy, which strings and handles are not, and only the
+                // component's Baker can turn a GameObject into its entity
+                if matches!(
+                    ty,
+                    FieldType::Vec(_)
+                        | FieldType::String
+                        | FieldType::EntityRef
+                        | FieldType::AssetPath
+                        | FieldType::Handle(_)
+                ) && !monobehaviour
+                {
+                    diagnostics.error(
+                        file,
+                        &f.ty,
+                        format!(
+                            "`{struct_name}.{field_name}` is a `{}`, which is only supported on #[unity_authoring] structs",
+                            ty.rust_name()
+                        ),
+                    );
+                    return None;
+                }
+
+                // resources are not components, so nothing resolves their entities, and configs
+                // are saved to a file, which can't point at a buffer or an entity either
+                let entity_ref = match &ty {
+                    FieldType::Vec(ty) => ty.as_ref(),
+                    ty => ty,
+                } == &FieldType::EntityRef;
+                let unsupported = match expected {
+                    "unity_resource" => entity_ref,
+                    "unity_config" => entity_ref || matches!(ty, FieldType::Vec(_)),
+                    _ => false,
+                };
+                if unsupported {
+                    diagnostics.error(
+                        file,
+                        &f.ty,
+                        format!(
+                            "`{struct_name}.{field_name}` is a `{}`, which is not supported on #[{expected}] structs",
+                            ty.rust_name()
+                        ),
+                    );
+                    return None;
+                }
+
+                let inspector = inspector::field_inspector(
+                    file,
+                    f,
+                    &ty,
+                    defaults.get(&field_name).copied(),
+                    monobehaviour,
+                    diagnostics,
+                );
+
+                Some(AuthoringField {
+                    name: field_name,
+                    ty,
+                    inspector,
+                })
+            })
+            .collect::<Vec<AuthoringField>>(),
+        syn::Fields::Unnamed(fields) => {
+            diagnostics.error(
+                file,
+                fields,
+                format!(
+                    "`{struct_name}` is a tuple struct, #[{expected}] structs need named fields"
+                ),
+            );
+            vec![]
+        }
+        syn::Fields::Unit => vec![],
+    };
+
+    // a component can be a tag, but a field or a config can't take up no space in C#
+    let unnamed = matches!(item.fields, syn::Fields::Unnamed(_));
+    if item.fields.is_empty() && !unnamed && ["unity_struct", "unity_config"].contains(&expected) {
+        diagnostics.error(
+            file,
+            &item.ident,
+            format!("`{struct_name}` has no fields, which is only supported on #[unity_authoring] structs"),
+        );
+    }
+
+    AuthoringStruct {
+        name: struct_name,
+        module: module.path.clone(),
+        fields,
+        options: AuthoringOptions::default(),
+    }
+}
+
+/// Parses the arguments of `#[unity_authoring(..)]` or `#[unity_resource(..)]` on `item`,
+/// `components` being the module of every component in the crate by name.
+fn authoring_options(
+    module: &SourceModule,
+    item: &syn::ItemStruct,
+    expected: &str,
+    components: &HashMap<String, Vec<String>>,
+    diagnostics: &mut Diagnostics,
+) -> AuthoringOptions {
+    let mut options = AuthoringOptions::default();
+    // a resource only needs its values, not a transform
+    if expected == "unity_resource" {
+        options.transform = Some(TransformUsage::None);
+    }
+    let Some(attr) = item
+        .attrs
+        .iter()
+        .find(|attr| attr.path().is_ident(expected))
+    else {
+        return options;
+    };
+    if matches!(attr.meta, syn::Meta::Path(_)) {
+        return options;
+    }
+
+    let result = attr.parse_nested_meta(|meta| {
+        if meta.path.is_ident("transform") {
+            let name: syn::LitStr = meta.value()?.parse()?;
+            let usage = TransformUsage::from_name(&name.value()).ok_or_else(|| {
+                syn::Error::new(
+                    name.span(),
+                    "expected \"none\", \"renderable\", \"dynamic\", \"world_space\", \"non_uniform_scale\" or \"manual_override\"",
+                )
+            })?;
+            options.transform = Some(usage);
+        } else if meta.path.is_ident("requires") {
+            let content;
+            let value = meta.value()?;
+            syn::bracketed!(content in value);
+            let paths = content.parse_terminated(syn::Path::parse_mod_style, syn::Token![,])?;
+            for path in paths {
+                // matched by name like field types
+                let name = path.segments.last().map(|segment| segment.ident.unraw().to_string());
+                match name.and_then(|name| Some((components.get(&name)?.clone(), name))) {
+                    Some((module, name)) => options.requires.push(RequiredComponent { name, module }),
+                    None => {
+                        return Err(syn::Error::new_spanned(
+                            &path,
+                            "required components must be #[unity_authoring] or #[unity_resource] structs",
+                        ))
+                    }
+                }
+            }
+        } else if meta.path.is_ident("menu") {
+            let menu: syn::LitStr = meta.value()?.parse()?;
+            options.menu = Some(menu.value());
+        } else {
+            return Err(meta.error("expected `transform`, `requires` or `menu`"));
+        }
+        Ok(())
+    });
+    if let Err(err) = result {
+        diagnostics.syn_error(&module.file, err);
+    }
+
+    options
+}
+
+fn find_enums_with_attr(
+    module: &SourceModule,
+    expected: &str,
+    names: &mut HashMap<String, String>,
+    diagnostics: &mut Diagnostics,
+) -> Vec<AuthoringEnum> {
+    let file = module.file.as_path();
+    module
+        .items
+        .iter()
+        .filter_map(|item| match item {
+            syn::Item::Enum(s) => Some(s),
+            _ => None,
+        })
+        .filter(|item| item.attrs.iter().any(|attr| attr.path().is_ident(expected)))
+        .map(|item| {
+            check_csharp_name(&item.ident, file, diagnostics);
+            check_unique_name(&item.ident, module, names, diagnostics);
+
+            let variants = item
+                .variants
+                .iter()
+                .enumerate()
+                .map(|(index, v)| {
+                    check_csharp_name(&v.ident, file, diagnostics);
+                    if !matches!(v.fields, syn::Fields::Unit) {
+                        diagnostics.error(
+                            file,
+                            &v.fields,
+                            format!("variant `{}` has fields, #[{expected}] enums only support unit variants", v.ident),
+                        );
+                    }
+                    // variants are sent as their index, so explicit values have to match it
+                    if let Some((_, discriminant)) = &v.discriminant {
+                        let matches_index = match discriminant {
+                            syn::Expr::Lit(syn::ExprLit {
+                                lit: syn::Lit::Int(value),
+                                ..
+                            }) => value.base10_parse::<usize>().is_ok_and(|value| value == index),
+                            _ => false,
+                        };
+                        if !matches_index {
+                            diagnostics.error(
+                                file,
+                                discriminant,
+                                format!("variant `{}` has to be {index}, #[{expected}] enums are sent as the index of the variant", v.ident),
+                            );
+                        }
+                    }
+
+                    v.ident.unraw().to_string()
+                })
+                .collect();
+
+            AuthoringEnum {
+                name: item.ident.unraw().to_string(),
+                module: module.path.clone(),
+                variants,
+            }
+        })
+        .collect()
+}
+
+fn check_csharp_name(ident: &syn::Ident, file: &Path, diagnostics: &mut Diagnostics) {
+    let name = ident.unraw().to_string();
+    if is_csharp_keyword(&name) {
+        diagnostics.error(
+            file,
+            ident,
+            format!("`{name}` is a reserved keyword in C#, please rename it"),
+        );
+    }
+}
+
+/// Generated files and the wire enums are keyed by the bare type name, so it has to be unique
+/// across the whole crate even though the types live in different modules.
+fn check_unique_name(
+    ident: &syn::Ident,
+    module: &SourceModule,
+    names: &mut HashMap<String, String>,
+    diagnostics: &mut Diagnostics,
+) {
+    let name = ident.unraw().to_string();
+    let full_path = module
+        .path
+        .iter()
+        .chain(std::iter::once(&name))
+        .cloned()
+        .collect::<Vec<_>>()
+        .join("::");
+
+    if let Some(existing) = names.insert(name.clone(), full_path.clone()) {
+        diagnostics.error(
+            &module.file,
+            ident,
+            format!("`{full_path}` has the same name as `{existing}`, unrust types need unique names across the crate"),
+        );
+    }
+}
+
+/// Maps a field's rust type to one of the types every backend knows how to lay out.
+fn map_rust_type(ty: &syn::Type, module: &SourceModule, model: &CodegenModel) -> Option<FieldType> {
+    let path = match ty {
+        syn::Type::Path(path) => path,
+        syn::Type::Array(array) => {
+            let syn::Expr::Lit(syn::ExprLit {
+                lit: syn::Lit::Int(len),
+                ..
+            }) = &array.len
+            else {
+                return None;
+            };
+
+            let elem = map_rust_type(&array.elem, module, model)?;
+            if !elem.fits_fixed_buffer() {
+                return None;
+            }
+
+            return Some(FieldType::Array(Box::new(elem), len.base10_parse().ok()?));
+        }
+        _ => return None,
+    };
+
+    if path.qself.is_some() {
+        return None;
+    }
+
+    // glam and bevy types are matched by name, so `Vec3`, `bevy::math::Vec3` and `glam::Vec3` all work
+    let last = path.path.segments.last()?;
+    if last.ident == "Vec" {
+        let syn::PathArguments::AngleBracketed(args) = &last.arguments else {
+            return None;
+        };
+        let [syn::GenericArgument::Type(elem)] = args.args.iter().collect::<Vec<_>>()[..] else {
+            return None;
+        };
+
+        let elem = map_rust_type(elem, module, model)?;
+        if matches!(elem, FieldType::Array(..) | FieldType::Vec(_)) {
+            return None;
+        }
+
+        return Some(FieldType::Vec(Box::new(elem)));
+    }
+    // any asset type works, only its name is kept for the schema
+    if last.ident == "Handle" {
+        let syn::PathArguments::AngleBracketed(args) = &last.arguments else {
+            return None;
+        };
+        let [syn::GenericArgument::Type(syn::Type::Path(asset))] =
+            args.args.iter().collect::<Vec<_>>()[..]
+        else {
+            return None;
+        };
+
+        let asset = asset.path.segments.last()?.ident.to_string();
+        return Some(FieldType::Handle(asset));
+    }
+    // `AssetPath<'static>`, the only lifetime a component can hold
+    if last.ident == "AssetPath" {
+        return Some(FieldType::AssetPath);
+    }
+    if !last.arguments.is_empty() {
+        return None;
+    }
+    if is_bevy_color(&path.path, module) {
+        return Some(FieldType::Color);
+    }
+
+    let ty = match last.ident.unraw().to_string().as_str() {
+        "bool" => FieldType::Bool,
+        "u8" => FieldType::U8,
+        "i8" => FieldType::I8,
+        "u16" => FieldType::U16,
+        "i16" => FieldType::I16,
+        "char" => FieldType::Char,
+        "f32" => FieldType::F32,
+        "f64" => FieldType::F64,
+        "i32" => FieldType::I32,
+        "i64" => FieldType::I64,
+        "u32" => FieldType::U32,
+        "u64" => FieldType::U64,
+        "Vec2" => FieldType::Vec2,
+        "Vec3" => FieldType::Vec3,
+        "Vec4" => FieldType::Vec4,
+        "Quat" => FieldType::Quat,
+        "Mat4" => FieldType::Mat4,
+        "String" => FieldType::String,
+        "UnityEntityRef" => FieldType::EntityRef,
+        "UnityCurve" => FieldType::Curve,
+        "UnityGradient" => FieldType::Gradient,
+        other => {
+            if let Some(item) = model.enums.iter().find(|item| item.name == other) {
+                FieldType::Enum(item.clone())
+            } else {
+                FieldType::Struct(
+                    model
+                        .structs
+                        .iter()
+                        .find(|item| item.name == other)?
+                        .clone(),
+                )
+            }
+        }
+    };
+
+    Some(ty)
+}
+
+/// Whether `path` is bevy's `Color`, written out or imported into the module, so a `Color`
+/// of the crate's own, eg: a `#[unity_struct]`, is not taken for it.
+fn is_bevy_color(path: &syn::Path, module: &SourceModule) -> bool {
+    const PATHS: [&str; 3] = [
+        "bevy::prelude::Color",
+        "bevy::render::color::Color",
+        "bevy::render::prelude::Color",
+    ];
+
+    // `unrust::bevy::..` and `bevy_render::..` are the same types
+    fn normalize(path: &str) -> String {
+        let path = path.trim_start_matches("::");
+        let path = path.strip_prefix("unrust::").unwrap_or(path);
+        match path.strip_prefix("bevy_render::") {
+            Some(rest) => format!("bevy::render::{rest}"),
+            None => path.to_string(),
+        }
+    }
+
+    fn imports(tree: &syn::UseTree, prefix: String, found: &mut Vec<(String, Option<String>)>) {
+        match tree {
+            syn::UseTree::Path(path) => {
+                imports(&path.tree, format!("{prefix}{}::", path.ident), found)
+            }
+            syn::UseTree::Name(name) => found.push((
+                format!("{prefix}{}", name.ident),
+                Some(name.ident.to_string()),
+            )),
+            syn::UseTree::Rename(rename) => found.push((
+                format!("{prefix}{}", rename.ident),
+                Some(rename.rename.to_string()),
+            )),
+            syn::UseTree::Glob(_) => found.push((format!("{prefix}Color"), None)),
+            syn::UseTree::Group(group) => {
+                for tree in &group.items {
+                    imports(tree, prefix.clone(), found);
+                }
+            }
+        }
+    }
+
+    let segments = path
+        .segments
+        .iter()
+        .map(|segment| segment.ident.to_string())
+        .collect::<Vec<_>>();
+    if segments.len() > 1 {
+        return PATHS.contains(&normalize(&segments.join("::")).as_str());
+    }
+
+    // an item in the module shadows anything glob imported
+    let name = &segments[0];
+    let defined = module.items.iter().any(|item| match item {
+        syn::Item::Struct(item) => item.ident == name,
+        syn::Item::Enum(item) => item.ident == name,
+        syn::Item::Type(item) => item.ident == name,
+        _ => false,
+    });
+    if defined {
+        return false;
+    }
+
+    let mut found = vec![];
+    for item in &module.items {
+        if let syn::Item::Use(item) = item {
+            imports(&item.tree, String::new(), &mut found);
+        }
+    }
+
+    found.iter().any(|(full, imported)| {
+        imported
+            .as_ref()
+            .map_or(name == "Color", |imported| imported == name)
+            && PATHS.contains(&normalize(full).as_str())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn field_type(source: &str) -> Option<FieldType> {
+        let items = syn::parse_file(source).unwrap().items;
+        let module = SourceModule {
+            path: vec![],
+            file: "lib.rs".into(),
+            items,
+        };
+        let field = module
+            .items
+            .iter()
+            .find_map(|item| match item {
+                syn::Item::Struct(item) if item.ident == "Tinted" => item.fields.iter().next(),
+                _ => None,
+            })
+            .unwrap();
+
+        let mut model = CodegenModel::default();
+        model.structs.push(AuthoringStruct {
+            name: "Color".into(),
+            module: vec![],
+            fields: vec![],
+            options: AuthoringOptions::default(),
+        });
+        map_rust_type(&field.ty, &module, &model)
+    }
+
+    fn discover(fixture: &str) -> Result<CodegenModel> {
+        let root = Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("fixtures/setup_order")
+            .join(fixture);
+        CodegenModel::discover(&Codegen::new(root, "unused"))
+    }
+
+    #[test]
+    fn setup_lists_types_in_discovery_order() {
+        let model = discover("listed.rs").unwrap();
+        assert_eq!(model.prefabs.len(), 2);
+    }
+
+    #[test]
+    fn reports_prefabs_listed_out_of_order() {
+        let err = discover("prefabs.rs").unwrap_err().to_string();
+        assert!(
+            err.contains("`prefabs` has to list every type in the order the codegen numbers them, which is the order they are found walking the modules: `prefabs = [Enemies, Pickups]`"),
+            "{err}"
+        );
+        assert!(err.contains("1 error(s)"), "{err}");
+    }
+
+    #[test]
+    fn checks_the_order_without_the_generator() {
+        let root = Path::new(env!("CARGO_MANIFEST_DIR")).join("fixtures/setup_order/components.rs");
+        let config = Codegen::new(root, "unused").generators([Generator::States]);
+        let err = CodegenModel::discover(&config).unwrap_err().to_string();
+        assert!(err.contains("`components = [Speed, Player]`"), "{err}");
+    }
+
+    #[test]
+    fn bevy_color_by_path_or_import() {
+        for source in [
+            "struct Tinted { tint: bevy::render::color::Color }",
+            "struct Tinted { tint: unrust::bevy::prelude::Color }",
+            "struct Tinted { tint: bevy_render::color::Color }",
+            "use bevy::prelude::*;\nstruct Tinted { tint: Color }",
+            "use unrust::bevy::prelude::{Color, Vec3};\nstruct Tinted { tint: Color }",
+            "use bevy_render::color::Color as Tint;\nstruct Tinted { tint: Tint }",
+        ] {
+            assert_eq!(field_type(source), Some(FieldType::Color), "{source}");
+        }
+    }
+
+    #[test]
+    fn other_colors_are_not_bevy_color() {
+        for source in [
+            // the crate's own `#[unity_struct] Color`
+            "struct Tinted { tint: Color }",
+            "use bevy::prelude::*;\nstruct Color {}\nstruct Tinted { tint: Color }",
+            "use palette::Color;\nstruct Tinted { tint: Color }",
+            "struct Tinted { tint: palette::Color }",
+        ] {
+            assert!(
+                !matches!(field_type(source), Some(FieldType::Color)),
+                "{source}"
+            );
+        }
+    }
+}
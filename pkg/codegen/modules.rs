@@ -0,0 +1,286 @@
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+
+use syn::ext::IdentExt;
+
+use crate::diagnostics::{is_csharp_keyword, Diagnostics};
+
+/// A rust module found while walking the crate, either a file or an inline `mod foo { .. }` block.
+pub(crate) struct SourceModule {
+    /// Module path relative to the crate root, empty for the root itself.
+    pub path: Vec<String>,
+    /// The file the module's items were parsed from.
+    pub file: PathBuf,
+    pub items: Vec<syn::Item>,
+}
+
+/// Parses `root` and every module reachable from it through `mod` declarations,
+/// following the same file lookup rules as rustc (`foo.rs`, `foo/mod.rs` and `#[path]`).
+pub(crate) fn collect_modules(root: &Path, diagnostics: &mut Diagnostics) -> Vec<SourceModule> {
+    let mut walker = ModuleWalker {
+        diagnostics,
+        visited: HashSet::default(),
+        modules: vec![],
+    };
+
+    let dir = root.parent().unwrap_or(Path::new(".")).to_path_buf();
+    if let Some(items) = walker.parse_file(root) {
+        walker.visit_items(items, root, vec![], &dir, &dir);
+    }
+
+    walker.modules
+}
+
+struct ModuleWalker<'a> {
+    diagnostics: &'a mut Diagnostics,
+    visited: HashSet<PathBuf>,
+    modules: Vec<SourceModule>,
+}
+
+impl<'a> ModuleWalker<'a> {
+    fn parse_file(&mut self, file: &Path) -> Option<Vec<syn::Item>> {
+        let canonical = file.canonicalize().unwrap_or(file.to_path_buf());
+        if !self.visited.insert(canonical) {
+            return None;
+        }
+
+        let contents = match std::fs::read_to_string(file) {
+            Ok(contents) => contents,
+            Err(err) => {
+                self.diagnostics.error(
+                    file,
+                    &proc_macro2::Span::call_site(),
+                    format!("failed to read file: {err}"),
+                );
+                return None;
+            }
+        };
+
+        match syn::parse_file(&contents) {
+            Ok(ast) => Some(ast.items),
+            Err(err) => {
+                self.diagnostics.syn_error(file, err);
+                None
+            }
+        }
+    }
+
+    /// `mod_dir` is where `mod foo;` looks for `foo.rs`, `path_dir` is what `#[path]` is relative to.
+    fn visit_items(
+        &mut self,
+        items: Vec<syn::Item>,
+        file: &Path,
+        path: Vec<String>,
+        mod_dir: &Path,
+        path_dir: &Path,
+    ) {
+        self.modules.push(SourceModule {
+            path: path.clone(),
+            file: file.to_path_buf(),
+            items: items.clone(),
+        });
+
+        for item in &items {
+            let syn::Item::Mod(module) = item else {
+                continue;
+            };
+
+            let name = module.ident.unraw().to_string();
+            if is_csharp_keyword(&name) {
+                self.diagnostics.error(
+                    file,
+                    &module.ident,
+                    format!("module `{name}` is a reserved keyword in C# and cannot be used as a namespace"),
+                );
+            }
+
+            let mut child_path = path.clone();
+            child_path.push(name.clone());
+
+            let path_attr = find_path_attr(module);
+            // a module behind a `#[cfg]` may only exist on other targets
+            let is_cfg = module.attrs.iter().any(|attr| attr.path().is_ident("cfg"));
+
+            if let Some((_, content)) = &module.content {
+                let dir = match &path_attr {
+                    Some(attr) => path_dir.join(attr),
+                    None => mod_dir.join(&name),
+                };
+
+                self.visit_items(content.clone(), file, child_path, &dir, &dir);
+                continue;
+            }
+
+            let (child_file, child_mod_dir) = match &path_attr {
+                Some(attr) => {
+                    let child_file = path_dir.join(attr);
+                    if !child_file.is_file() {
+                        if is_cfg {
+                            continue;
+                        }
+                        self.diagnostics.error(
+                            file,
+                            &module.ident,
+                            format!(
+                                "file not found for module `{name}`, expected {}",
+                                child_file.display()
+                            ),
+                        );
+                        continue;
+                    }
+
+                    let dir = child_file.parent().unwrap_or(path_dir).to_path_buf();
+                    (child_file, dir)
+                }
+                None => {
+                    let flat = mod_dir.join(format!("{name}.rs"));
+                    let nested = mod_dir.join(&name).join("mod.rs");
+                    if flat.is_file() {
+                        (flat, mod_dir.join(&name))
+                    } else if nested.is_file() {
+                        (nested, mod_dir.join(&name))
+                    } else if is_cfg {
+                        continue;
+                    } else {
+                        self.diagnostics.error(
+                            file,
+                            &module.ident,
+                            format!(
+                                "file not found for module `{name}`, expected {} or {}",
+                                flat.display(),
+                                nested.display()
+                            ),
+                        );
+                        continue;
+                    }
+                }
+            };
+
+            let child_path_dir = child_file.parent().unwrap_or(mod_dir).to_path_buf();
+            if let Some(child_items) = self.parse_file(&child_file) {
+                self.visit_items(
+                    child_items,
+                    &child_file,
+                    child_path,
+                    &child_mod_dir,
+                    &child_path_dir,
+                );
+            }
+        }
+    }
+}
+
+fn find_path_attr(module: &syn::ItemMod) -> Option<String> {
+    module.attrs.iter().find_map(|attr| {
+        if !attr.path().is_ident("path") {
+            return None;
+        }
+
+        let syn::Meta::NameValue(meta) = &attr.meta else {
+            return None;
+        };
+
+        match &meta.value {
+            syn::Expr::Lit(syn::ExprLit {
+                lit: syn::Lit::Str(lit),
+                ..
+            }) => Some(lit.value()),
+            _ => None,
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fixture() -> PathBuf {
+        Path::new(env!("CARGO_MANIFEST_DIR")).join("fixtures/modules")
+    }
+
+    fn struct_names(module: &SourceModule) -> Vec<String> {
+        module
+            .items
+            .iter()
+            .filter_map(|item| match item {
+                syn::Item::Struct(item) => Some(item.ident.to_string()),
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn follows_rustc_file_lookup() {
+        let root = fixture();
+        let mut diagnostics = Diagnostics::default();
+        let modules = collect_modules(&root.join("lib.rs"), &mut diagnostics);
+        diagnostics.finish().unwrap();
+
+        let found = modules
+            .iter()
+            .map(|module| {
+                let file = module.file.strip_prefix(&root).unwrap();
+                (module.path.join("::"), file.to_str().unwrap().to_string())
+            })
+            .collect::<Vec<_>>();
+        let expected = [
+            ("", "lib.rs"),
+            ("flat", "flat.rs"),
+            ("flat::deeper", "flat/deeper.rs"),
+            ("nested", "nested/mod.rs"),
+            ("nested::sibling", "nested/sibling.rs"),
+            ("pathed", "renamed/custom.rs"),
+            ("pathed::beside", "renamed/beside.rs"),
+            ("inline", "lib.rs"),
+            ("inline::child", "inline/child.rs"),
+            ("inline::moved", "lib.rs"),
+            ("inline::moved::leaf", "inline/elsewhere/leaf.rs"),
+            ("gated", "gated.rs"),
+        ]
+        .map(|(path, file)| (path.to_string(), file.to_string()));
+        assert_eq!(found, expected);
+    }
+
+    #[test]
+    fn inline_modules_only_hold_their_own_items() {
+        let mut diagnostics = Diagnostics::default();
+        let modules = collect_modules(&fixture().join("lib.rs"), &mut diagnostics);
+
+        let inline = modules.iter().find(|module| module.path == ["inline"]);
+        assert_eq!(struct_names(inline.unwrap()), ["Inline"]);
+        assert_eq!(struct_names(&modules[0]), ["Root"]);
+        let leaf = modules
+            .iter()
+            .find(|module| module.path.ends_with(&["leaf".into()]));
+        assert_eq!(struct_names(leaf.unwrap()), ["Leaf"]);
+    }
+
+    #[test]
+    fn reports_missing_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let dir = dir.path();
+        std::fs::write(
+            dir.join("lib.rs"),
+            "mod present;\n\nmod absent;\n\n#[path = \"nowhere.rs\"]\nmod moved;\n",
+        )
+        .unwrap();
+        std::fs::write(dir.join("present.rs"), "pub struct Present;\n").unwrap();
+
+        let mut diagnostics = Diagnostics::default();
+        let modules = collect_modules(&dir.join("lib.rs"), &mut diagnostics);
+        let errors = diagnostics.finish().unwrap_err().diagnostics;
+
+        assert_eq!(modules.len(), 2);
+        let found = errors
+            .iter()
+            .map(|error| (error.line, error.message.split(',').next().unwrap()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            found,
+            [
+                (3, "file not found for module `absent`"),
+                (6, "file not found for module `moved`")
+            ]
+        );
+    }
+}
@@ -0,0 +1,488 @@
+use anyhow::Context;
+use anyhow::Result;
+use genco::fmt;
+use genco::prelude::*;
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::{Path, PathBuf};
+
+/// Lists the files codegen owns in the output folder, so only those are ever removed.
+/// Unity ignores dot files, so this does not get imported as an asset.
+const MANIFEST_FILE: &str = ".unrust-generated";
+const MANIFEST_HEADER: &str = "# files generated by unrust, do not edit";
+
+/// All files produced by a backend, rendered in memory before anything touches the disk.
+/// Names are paths relative to the backend's output folder.
+#[derive(Default)]
+pub struct GeneratedFiles {
+    files: BTreeMap<String, String>,
+}
+
+impl GeneratedFiles {
+    pub(crate) fn add(&mut self, name: &str, tokens: csharp::Tokens) -> Result<()> {
+        let fmt = fmt::Config::from_lang::<Csharp>().with_indentation(fmt::Indentation::Space(4));
+        let config = csharp::Config::default();
+
+        let mut w = fmt::FmtWriter::new(String::new());
+        tokens
+            .format_file(&mut w.as_formatter(&fmt), &config)
+            .with_context(|| format!("could not format {name}"))?;
+
+        self.files.insert(name.to_string(), w.into_inner());
+        Ok(())
+    }
+
+    pub fn add_text(&mut self, name: &str, contents: String) {
+        self.files.insert(name.to_string(), contents);
+    }
+
+    /// The contents of a file added so far.
+    pub fn get(&self, name: &str) -> Option<&str> {
+        self.files.get(name).map(String::as_str)
+    }
+
+    /// Writes files whose contents changed, adds a `.meta` for new ones and removes files
+    /// that an earlier run generated but this one did not. Anything else in the folder is left alone.
+    pub(crate) fn write(self, base_folder: &Path) -> Result<()> {
+        std::fs::create_dir_all(base_folder)
+            .with_context(|| format!("failed to create {}", base_folder.display()))?;
+
+        for change in self.changes(base_folder)? {
+            match change {
+                Change::Create(name, contents) | Change::Update(name, contents) => {
+                    let path = base_folder.join(&name);
+                    if let Some(parent) = path.parent() {
+                        std::fs::create_dir_all(parent)
+                            .with_context(|| format!("failed to create {}", parent.display()))?;
+                    }
+                    std::fs::write(&path, contents)
+                        .with_context(|| format!("failed to write {}", path.display()))?;
+                }
+                Change::Move(from, to) => {
+                    let (from, to) = (base_folder.join(&from), base_folder.join(&to));
+                    if let Some(parent) = to.parent() {
+                        std::fs::create_dir_all(parent)
+                            .with_context(|| format!("failed to create {}", parent.display()))?;
+                    }
+                    std::fs::rename(&from, &to).with_context(|| {
+                        format!("failed to move {} to {}", from.display(), to.display())
+                    })?;
+                    remove_empty_folders(&from, base_folder);
+                }
+                Change::Remove(name) => {
+                    let path = base_folder.join(&name);
+                    std::fs::remove_file(&path)
+                        .with_context(|| format!("failed to remove {}", path.display()))?;
+                    remove_empty_folders(&path, base_folder);
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Compares what would be generated with what is on disk without touching anything.
+    pub(crate) fn check(self, base_folder: &Path) -> Result<()> {
+        let mut error = CheckError {
+            folder: base_folder.to_path_buf(),
+            ..Default::default()
+        };
+
+        for change in self.changes(base_folder)? {
+            match change {
+                Change::Create(name, _) => error.missing.push(name),
+                Change::Update(name, contents) => {
+                    let existing = std::fs::read_to_string(base_folder.join(&name))?;
+                    let line = first_difference(&existing, &contents);
+                    error.stale.push((name, line));
+                }
+                Change::Move(from, to) => error.moved.push((from, to)),
+                Change::Remove(name) => error.extra.push(name),
+            }
+        }
+
+        if error.is_empty() {
+            return Ok(());
+        }
+
+        Err(error.into())
+    }
+
+    fn changes(&self, base_folder: &Path) -> Result<Vec<Change>> {
+        let previous = read_manifest(base_folder)?;
+        let mut changes = vec![];
+
+        // the meta of a script that moved, eg: to a renamed module's folder, goes with it
+        // so prefabs and scenes keep finding the script by its guid
+        let mut moved = BTreeSet::new();
+        for stale in previous
+            .iter()
+            .filter(|name| !self.files.contains_key(*name))
+        {
+            if base_folder.join(stale).exists() {
+                changes.push(Change::Remove(stale.clone()));
+            }
+
+            let meta = format!("{stale}.meta");
+            if !base_folder.join(&meta).exists() {
+                continue;
+            }
+            let target = self.files.keys().find(|name| {
+                file_name(name) == file_name(stale)
+                    && !moved.contains(*name)
+                    && !base_folder.join(format!("{name}.meta")).exists()
+            });
+            match target {
+                Some(name) => {
+                    moved.insert(name.clone());
+                    changes.push(Change::Move(meta, format!("{name}.meta")));
+                }
+                None => changes.push(Change::Remove(meta)),
+            }
+        }
+
+        let manifest = std::iter::once(MANIFEST_HEADER)
+            .chain(self.files.keys().map(String::as_str))
+            .fold(String::new(), |acc, line| acc + line + "\n");
+
+        // unity creates metas for anything else itself, scripts need a stable guid
+        let metas = self
+            .files
+            .keys()
+            .filter(|name| name.ends_with(".cs"))
+            .map(|name| (format!("{name}.meta"), script_meta(name)));
+
+        let files = self
+            .files
+            .iter()
+            .map(|(name, contents)| (name.clone(), contents.clone()))
+            .chain(std::iter::once((MANIFEST_FILE.to_string(), manifest)));
+
+        for (name, contents) in files {
+            match std::fs::read_to_string(base_folder.join(&name)) {
+                Ok(existing) if existing == contents => {}
+                Ok(_) => changes.push(Change::Update(name, contents)),
+                Err(_) => changes.push(Change::Create(name, contents)),
+            }
+        }
+
+        // never replace an existing meta, unity scenes reference scripts by its guid
+        for (name, contents) in metas {
+            let script = name.trim_end_matches(".meta");
+            if !moved.contains(script) && !base_folder.join(&name).exists() {
+                changes.push(Change::Create(name, contents));
+            }
+        }
+
+        Ok(changes)
+    }
+}
+
+enum Change {
+    Create(String, String),
+    Update(String, String),
+    Move(String, String),
+    Remove(String),
+}
+
+fn file_name(name: &str) -> &str {
+    name.rsplit('/').next().unwrap_or(name)
+}
+
+/// Removes the module folders `path` was in once they are empty, up to `base_folder`.
+fn remove_empty_folders(path: &Path, base_folder: &Path) {
+    // stops at the first one that is not empty
+    let mut dir = path.parent();
+    while let Some(parent) = dir.filter(|dir| *dir != base_folder) {
+        if std::fs::remove_dir(parent).is_err() {
+            break;
+        }
+        dir = parent.parent();
+    }
+}
+
+/// Returned by check mode when the generated files on disk do not match the rust types.
+#[derive(Debug, Default)]
+pub struct CheckError {
+    pub folder: PathBuf,
+    /// Files whose contents differ, with the first line that differs.
+    pub stale: Vec<(String, usize)>,
+    /// Files that would be created.
+    pub missing: Vec<String>,
+    /// Files generated by an earlier run that would be removed.
+    pub extra: Vec<String>,
+    /// Metas that would move along with their script, from and to.
+    pub moved: Vec<(String, String)>,
+}
+
+impl CheckError {
+    fn is_empty(&self) -> bool {
+        self.stale.is_empty()
+            && self.missing.is_empty()
+            && self.extra.is_empty()
+            && self.moved.is_empty()
+    }
+}
+
+impl std::fmt::Display for CheckError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(
+            f,
+            "generated code in {} is out of date, rerun the codegen:",
+            self.folder.display()
+        )?;
+        for (name, line) in &self.stale {
+            writeln!(f, "~ {name} (differs from line {line})")?;
+        }
+        for name in &self.missing {
+            writeln!(f, "+ {name}")?;
+        }
+        for name in &self.extra {
+            writeln!(f, "- {name}")?;
+        }
+        for (from, to) in &self.moved {
+            writeln!(f, "> {from} -> {to}")?;
+        }
+
+        Ok(())
+    }
+}
+
+impl std::error::Error for CheckError {}
+
+fn first_difference(existing: &str, expected: &str) -> usize {
+    let mut existing_lines = existing.lines();
+    let mut expected_lines = expected.lines();
+    let mut line = 1;
+    loop {
+        match (existing_lines.next(), expected_lines.next()) {
+            (Some(a), Some(b)) if a == b => line += 1,
+            _ => return line,
+        }
+    }
+}
+
+fn read_manifest(base_folder: &Path) -> Result<BTreeSet<String>> {
+    let path = base_folder.join(MANIFEST_FILE);
+    if !path.exists() {
+        return Ok(legacy_outputs(base_folder));
+    }
+
+    let contents = std::fs::read_to_string(&path)
+        .with_context(|| format!("failed to read {}", path.display()))?;
+
+    Ok(contents
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        // only relative paths below the folder are ever written, ignore anything that could escape it
+        .filter(|line| {
+            !line.contains(['\\', ':'])
+                && line.split('/').all(|part| !part.is_empty() && part != "..")
+        })
+        .map(str::to_string)
+        .collect())
+}
+
+/// Files written before the manifest existed, which all went flat into the folder. They are
+/// only looked for without a manifest, so the first run cleans them up like any stale file.
+fn legacy_outputs(base_folder: &Path) -> BTreeSet<String> {
+    const FILES: [&str; 3] = ["UnrustHooks.cs", "UnrustState.cs", "UnrustComponent.cs"];
+
+    let Ok(entries) = std::fs::read_dir(base_folder) else {
+        return BTreeSet::default();
+    };
+
+    entries
+        .filter_map(|entry| entry.ok()?.file_name().into_string().ok())
+        .filter(|name| FILES.contains(&name.as_str()) || name.ends_with("Authoring.cs"))
+        // the old codegen always used this namespace, anything else was written by hand
+        .filter(|name| {
+            std::fs::read_to_string(base_folder.join(name))
+                .is_ok_and(|contents| contents.contains("namespace unrust.userland"))
+        })
+        .collect()
+}
+
+fn script_meta(name: &str) -> String {
+    format!(
+        "fileFormatVersion: 2\n\
+         guid: {}\n\
+         MonoImporter:\n  \
+         externalObjects: {{}}\n  \
+         serializedVersion: 2\n  \
+         defaultReferences: []\n  \
+         executionOrder: 0\n  \
+         icon: {{instanceID: 0}}\n  \
+         userData: \n  \
+         assetBundleName: \n  \
+         assetBundleVariant: \n",
+        stable_guid(name)
+    )
+}
+
+/// A unity style guid (32 hex characters) derived from the file name with 128 bit FNV-1a,
+/// so regenerating a deleted meta file gives back the same guid on any machine. The folder is
+/// left out, the file moves with its module while C# type names are unique across the crate.
+fn stable_guid(name: &str) -> String {
+    const OFFSET: u128 = 0x6c62272e07bb014262b821756295c58d;
+    const PRIME: u128 = 0x0000000001000000000000000000013b;
+
+    let hash = format!("unrust/{}", file_name(name))
+        .bytes()
+        .fold(OFFSET, |hash, byte| {
+            (hash ^ byte as u128).wrapping_mul(PRIME)
+        });
+
+    format!("{hash:032x}")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn files(entries: &[(&str, &str)]) -> GeneratedFiles {
+        let mut files = GeneratedFiles::default();
+        for (name, contents) in entries {
+            files.add_text(name, contents.to_string());
+        }
+        files
+    }
+
+    fn changes(files: &GeneratedFiles, base_folder: &Path) -> Vec<String> {
+        let mut changes = files
+            .changes(base_folder)
+            .unwrap()
+            .into_iter()
+            .map(|change| match change {
+                Change::Create(name, _) => format!("+ {name}"),
+                Change::Update(name, _) => format!("~ {name}"),
+                Change::Move(from, to) => format!("> {from} -> {to}"),
+                Change::Remove(name) => format!("- {name}"),
+            })
+            .collect::<Vec<_>>();
+        changes.sort();
+        changes
+    }
+
+    #[test]
+    fn tracks_added_modified_and_removed_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let dir = dir.path();
+
+        let first = files(&[("A.cs", "a"), ("types/B.cs", "b")]);
+        assert_eq!(
+            changes(&first, dir),
+            [
+                "+ .unrust-generated",
+                "+ A.cs",
+                "+ A.cs.meta",
+                "+ types/B.cs",
+                "+ types/B.cs.meta"
+            ]
+        );
+        first.write(dir).unwrap();
+
+        let unchanged = files(&[("A.cs", "a"), ("types/B.cs", "b")]);
+        assert!(changes(&unchanged, dir).is_empty());
+
+        let modified = files(&[("A.cs", "changed"), ("types/B.cs", "b")]);
+        assert_eq!(changes(&modified, dir), ["~ A.cs"]);
+
+        let removed = files(&[("A.cs", "a")]);
+        assert_eq!(
+            changes(&removed, dir),
+            ["- types/B.cs", "- types/B.cs.meta", "~ .unrust-generated"]
+        );
+        removed.write(dir).unwrap();
+        assert!(!dir.join("types").exists());
+    }
+
+    #[test]
+    fn keeps_files_it_did_not_generate() {
+        let dir = tempfile::tempdir().unwrap();
+        let dir = dir.path();
+        std::fs::write(dir.join("Mine.cs"), "namespace game {}").unwrap();
+        files(&[("A.cs", "a")]).write(dir).unwrap();
+
+        assert!(changes(&files(&[]), dir)
+            .iter()
+            .all(|change| !change.contains("Mine")));
+        assert!(dir.join("Mine.cs").exists());
+    }
+
+    #[test]
+    fn removes_outputs_from_before_the_manifest() {
+        let dir = tempfile::tempdir().unwrap();
+        let dir = dir.path();
+        let legacy = "namespace unrust.userland\n{\n}\n";
+        for name in ["UnrustHooks.cs", "GoneAuthoring.cs", "KeptAuthoring.cs"] {
+            std::fs::write(dir.join(name), legacy).unwrap();
+            std::fs::write(dir.join(format!("{name}.meta")), "guid: 1").unwrap();
+        }
+        std::fs::write(dir.join("HandAuthoring.cs"), "namespace game {}").unwrap();
+
+        let current = files(&[
+            ("UnrustHooks.cs", legacy),
+            ("types/KeptAuthoring.cs", legacy),
+        ]);
+        assert_eq!(
+            changes(&current, dir),
+            [
+                "+ .unrust-generated",
+                "+ types/KeptAuthoring.cs",
+                "- GoneAuthoring.cs",
+                "- GoneAuthoring.cs.meta",
+                "- KeptAuthoring.cs",
+                "> KeptAuthoring.cs.meta -> types/KeptAuthoring.cs.meta"
+            ]
+        );
+
+        // only once, afterwards the manifest decides
+        current.write(dir).unwrap();
+        assert_eq!(
+            std::fs::read_to_string(dir.join("types/KeptAuthoring.cs.meta")).unwrap(),
+            "guid: 1"
+        );
+        std::fs::write(dir.join("LaterAuthoring.cs"), legacy).unwrap();
+        assert!(changes(
+            &files(&[
+                ("UnrustHooks.cs", legacy),
+                ("types/KeptAuthoring.cs", legacy)
+            ]),
+            dir
+        )
+        .is_empty());
+        assert!(dir.join("HandAuthoring.cs").exists());
+    }
+
+    #[test]
+    fn moved_scripts_keep_their_guid() {
+        let dir = tempfile::tempdir().unwrap();
+        let dir = dir.path();
+        files(&[("types/SpeedAuthoring.cs", "a"), ("Hooks.cs", "h")])
+            .write(dir)
+            .unwrap();
+        let meta = std::fs::read_to_string(dir.join("types/SpeedAuthoring.cs.meta")).unwrap();
+
+        // the module was renamed
+        let renamed = files(&[("movement/SpeedAuthoring.cs", "a"), ("Hooks.cs", "h")]);
+        assert_eq!(
+            changes(&renamed, dir),
+            [
+                "+ movement/SpeedAuthoring.cs",
+                "- types/SpeedAuthoring.cs",
+                "> types/SpeedAuthoring.cs.meta -> movement/SpeedAuthoring.cs.meta",
+                "~ .unrust-generated"
+            ]
+        );
+        renamed.write(dir).unwrap();
+        assert_eq!(
+            std::fs::read_to_string(dir.join("movement/SpeedAuthoring.cs.meta")).unwrap(),
+            meta
+        );
+        assert!(!dir.join("types").exists());
+
+        // a meta written again from scratch gets the same guid wherever the script is
+        assert_eq!(script_meta("SpeedAuthoring.cs"), meta);
+    }
+}
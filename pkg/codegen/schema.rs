@@ -0,0 +1,162 @@
+use anyhow::Result;
+use inbuilt::{FieldSchema, TypeSchema};
+use serde::Serialize;
+
+use crate::model::{AuthoringEnum, AuthoringStruct, CodegenModel};
+use crate::output::GeneratedFiles;
+use crate::unity::csharp_type;
+use crate::Codegen;
+
+/// Bumped whenever the shape of the json changes, so tools can reject files they do not understand.
+const SCHEMA_VERSION: u32 = 3;
+
+/// Everything sent between unity and bevy, written next to the C# so tools don't need to parse rust.
+#[derive(Serialize)]
+struct Schema {
+    version: u32,
+    inbuilt: Vec<TypeSchema>,
+    components: Vec<TypeSchema>,
+    /// `#[unity_resource]`s, whose `CustomType` follows the components.
+    resources: Vec<TypeSchema>,
+    /// `#[unity_config]`s, exported to a file with this layout instead of being sent.
+    configs: Vec<TypeSchema>,
+    /// `#[unity_enum]` field types.
+    enums: Vec<EnumSchema>,
+    /// `#[unity_struct]` field types.
+    structs: Vec<TypeSchema>,
+    states: Vec<EnumSchema>,
+    prefabs: Vec<EnumSchema>,
+}
+
+#[derive(Serialize)]
+struct EnumSchema {
+    name: String,
+    rust_path: String,
+    csharp_type: String,
+    /// `CustomStateType` for states, `RESOURCE_ID` for prefabs, none for field enums.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    wire_id: Option<u8>,
+    variants: Vec<VariantSchema>,
+}
+
+#[derive(Serialize)]
+struct VariantSchema {
+    name: String,
+    value: u8,
+}
+
+pub(crate) fn generate_schema(
+    config: &Codegen,
+    model: &CodegenModel,
+    files: &mut GeneratedFiles,
+) -> Result<()> {
+    let schema = Schema {
+        version: SCHEMA_VERSION,
+        inbuilt: inbuilt::inbuilt_schema(),
+        components: model
+            .components
+            .iter()
+            .enumerate()
+            .map(|(index, item)| struct_schema(config, Some(index), item))
+            .collect(),
+        resources: model
+            .resources
+            .iter()
+            .enumerate()
+            .map(|(index, item)| struct_schema(config, Some(model.components.len() + index), item))
+            .collect(),
+        configs: model
+            .configs
+            .iter()
+            .map(|item| struct_schema(config, None, item))
+            .collect(),
+        enums: model
+            .enums
+            .iter()
+            .map(|item| enum_schema(config, None, item, item.name.clone()))
+            .collect(),
+        structs: model
+            .structs
+            .iter()
+            .map(|item| struct_schema(config, None, item))
+            .collect(),
+        states: model
+            .states
+            .iter()
+            .enumerate()
+            .map(|(index, item)| enum_schema(config, Some(index), item, item.name.clone()))
+            .collect(),
+        // prefabs only exist as the authoring MonoBehaviour on the C# side
+        prefabs: model
+            .prefabs
+            .iter()
+            .enumerate()
+            .map(|(index, item)| {
+                let authoring_name = format!("{}{}", item.name, config.authoring_suffix);
+                enum_schema(config, Some(index), item, authoring_name)
+            })
+            .collect(),
+    };
+
+    let json = serde_json::to_string_pretty(&schema)? + "\n";
+    files.add_text(&config.schema_file, json);
+    Ok(())
+}
+
+fn struct_schema(config: &Codegen, index: Option<usize>, item: &AuthoringStruct) -> TypeSchema {
+    let layout = item.layout();
+    let fields = item
+        .fields
+        .iter()
+        .zip(&layout.offsets)
+        .map(|(field, offset)| FieldSchema {
+            name: field.name.clone(),
+            csharp_name: field.name.clone(),
+            rust_type: field.ty.rust_name(),
+            csharp_type: csharp_type(config, &field.ty),
+            offset: *offset,
+            size: field.ty.size(),
+        })
+        .collect();
+
+    TypeSchema {
+        name: item.name.clone(),
+        rust_path: rust_path(&item.module, &item.name),
+        csharp_type: format!("{}.{}", config.namespace_for(&item.module), item.name),
+        wire_id: index.map(|index| index as u8),
+        size: layout.size,
+        align: layout.align,
+        fields,
+    }
+}
+
+fn enum_schema(
+    config: &Codegen,
+    index: Option<usize>,
+    item: &AuthoringEnum,
+    csharp_name: String,
+) -> EnumSchema {
+    EnumSchema {
+        name: item.name.clone(),
+        rust_path: rust_path(&item.module, &item.name),
+        csharp_type: format!("{}.{csharp_name}", config.namespace_for(&item.module)),
+        wire_id: index.map(|index| index as u8),
+        variants: item
+            .variants
+            .iter()
+            .enumerate()
+            .map(|(value, name)| VariantSchema {
+                name: name.clone(),
+                value: value as u8,
+            })
+            .collect(),
+    }
+}
+
+fn rust_path(module: &[String], name: &str) -> String {
+    std::iter::once("crate")
+        .chain(module.iter().map(String::as_str))
+        .chain(std::iter::once(name))
+        .collect::<Vec<_>>()
+        .join("::")
+}
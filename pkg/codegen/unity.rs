@@ -0,0 +1,1225 @@
+use anyhow::Result;
+use genco::prelude::*;
+
+use crate::model::{
+    AuthoringEnum, AuthoringField, AuthoringOptions, AuthoringStruct, CodegenModel, FieldType,
+    Layout,
+};
+use crate::output::GeneratedFiles;
+use crate::{schema, Codegen, CodegenBackend, DefaultValue, Generator, Inspector, Validation};
+
+/// The C# side of unrust: authoring MonoBehaviours with their Bakers, the wire types
+/// and the hooks the runtime calls to collect custom components and states.
+pub struct UnityBackend;
+
+impl CodegenBackend for UnityBackend {
+    fn name(&self) -> &str {
+        "unity"
+    }
+
+    fn generate(
+        &self,
+        config: &Codegen,
+        model: &CodegenModel,
+        files: &mut GeneratedFiles,
+    ) -> Result<()> {
+        if config.runs(Generator::Schema) {
+            schema::generate_schema(config, model, files)?;
+        }
+
+        generate_enums_csharp(config, &model.enums, files)?;
+        generate_structs_csharp(config, &model.structs, files)?;
+        // resources are components in unity, sent after the others
+        let components = model
+            .components
+            .iter()
+            .chain(&model.resources)
+            .cloned()
+            .collect();
+        let custom_comps =
+            generate_components_csharp(config, components, model.custom_data_layout(), files)?;
+        generate_configs_csharp(config, &model.configs, files)?;
+        let custom_states = generate_states_csharp(config, model.states.clone(), files)?;
+        generate_prefabs_csharp(config, model.prefabs.clone(), files)?;
+
+        generate_hooks(config, model, files, custom_comps, custom_states)
+    }
+}
+
+fn generate_hooks(
+    config: &Codegen,
+    model: &CodegenModel,
+    files: &mut GeneratedFiles,
+    custom_comps: csharp::Tokens,
+    custom_states: csharp::Tokens,
+) -> Result<()> {
+    let runtime_initialize = &csharp::import("UnityEngine", "RuntimeInitializeOnLoadMethod");
+    let runtime_initialize_load = &csharp::import("UnityEngine", "RuntimeInitializeLoadType");
+    let unrust_native = &csharp::import("unrust.runtime", "NativeWrapper");
+    let entity_manager = &csharp::import("Unity.Entities", "EntityManager");
+    let entity = &csharp::import("Unity.Entities", "Entity");
+    let create_callback = &csharp::import("unrust.runtime", "CustomCreateCallback");
+    let debug = &csharp::import("UnityEngine", "Debug");
+    let unsafe_utility = &csharp::import("Unity.Collections.LowLevel.Unsafe", "UnsafeUtility");
+
+    // components are copied as raw bytes into rust, so a size mismatch would corrupt them
+    let layout_checks = model
+        .components
+        .iter()
+        .chain(&model.resources)
+        .chain(&model.configs)
+        .filter(|component| !component.is_tag())
+        .map(|component| {
+            let comp_type =
+                csharp::import(config.namespace_for(&component.module), &component.name);
+            let size = component.layout().size;
+            let message = format!(
+                "{} is {size} bytes in rust, rerun the unrust codegen",
+                component.name
+            );
+            quote! {
+                $debug.Assert($unsafe_utility.SizeOf<$comp_type>() == $size, $(quoted(message)));
+            }
+        });
+
+    let hooks: csharp::Tokens = quote! {
+        namespace $(&config.namespace)
+        {
+            public static class UnrustHooks
+            {
+                [$runtime_initialize($runtime_initialize_load.BeforeSceneLoad)]
+                static void Initialize()
+                {
+                    $(for n in layout_checks => $n)
+                    $unrust_native.CustomCreates = CreateCallback;
+                }
+
+                public static unsafe ulong CreateCallback($entity_manager manager, $entity entity, $create_callback cb)
+                {
+                    var count = 0;
+                    var arr = new CustomData[CustomComponents.ComponentCount];
+
+                    $(custom_comps)
+
+                    var stateCount = 0;
+                    var stateArr = new CustomState[CustomState.CustomStateCount];
+
+                    $(custom_states)
+
+                    fixed (void* ptr = arr)
+                    {
+                        fixed (void* state = stateArr)
+                        {
+                            return cb(ptr, (nuint)count, state, (nuint)stateCount);
+                        }
+                    }
+                }
+            }
+        }
+    };
+
+    files.add(&config.hooks_file, hooks)
+}
+
+/// `#[unity_enum]`s as C# enums, which the inspector shows as a dropdown.
+fn generate_enums_csharp(
+    config: &Codegen,
+    enums: &[AuthoringEnum],
+    files: &mut GeneratedFiles,
+) -> Result<()> {
+    enums.iter().try_for_each(|item| {
+        let variants = item.variants.iter().enumerate().map(|(index, name)| {
+            quote! {
+                $['\r']$(name.as_str()) = $index,
+            }
+        });
+
+        let tokens: csharp::Tokens = quote! {
+            namespace $(config.namespace_for(&item.module))
+            {
+                public enum $(&item.name) : byte
+                {
+                    $(for n in variants => $n)
+                }
+            }
+        };
+
+        files.add(&config.type_file(&item.module, &item.name), tokens)
+    })
+}
+
+fn generate_prefabs_csharp(
+    config: &Codegen,
+    enums: Vec<AuthoringEnum>,
+    files: &mut GeneratedFiles,
+) -> Result<()> {
+    let monobehaviour = &csharp::import("UnityEngine", "MonoBehaviour");
+    let baker = &csharp::import("Unity.Entities", "Baker");
+    let spawnable = &csharp::import("unrust.runtime", "UnrustSpawnable");
+
+    let enums = enums
+        .into_iter()
+        .enumerate()
+        .map(|(index, prefab)| {
+            let enum_name = prefab.name;
+            let authoring_name = format!("{enum_name}{}", config.authoring_suffix);
+            let variants = prefab.variants.iter();
+            let variant_fields = variants.clone().map(|name| {
+                quote! {
+                    $['\r']public GameObject $(name);
+                }
+            });
+
+            let count = index;
+
+            let author_fields = variants.map(|name| {
+                quote! {
+                    buffer.Add(GetEntity(authoring.$(name), TransformUsageFlags.Dynamic));
+                }
+            });
+
+            let namespace = config.namespace_for(&prefab.module);
+            let comp: csharp::Tokens = quote! {
+                namespace $(&namespace)
+                {
+                    public class $(&authoring_name) : $monobehaviour {
+                        $(for n in variant_fields => $n)
+
+                        public const int RESOURCE_ID = $count;
+
+                        class Baker : $baker<$(&authoring_name)>
+                        {
+                            public override void Bake($(&authoring_name) authoring)
+                            {
+                                var containerEntity = GetEntity(TransformUsageFlags.None);
+                                var buffer = AddBuffer<$spawnable>(containerEntity).Reinterpret<Entity>();
+
+                                $(for n in author_fields => $n)
+
+                                AddComponent<UnrustResourceID>(containerEntity, new UnrustResourceID { Value =  $count});
+                            }
+                        }
+                    }
+                }
+            };
+
+            (config.authoring_file(&prefab.module, &enum_name), comp)
+        });
+
+    enums
+        .clone()
+        .try_for_each(|(file, comp)| files.add(&file, comp))?;
+
+    Ok(())
+}
+
+fn generate_states_csharp(
+    config: &Codegen,
+    enums: Vec<AuthoringEnum>,
+    files: &mut GeneratedFiles,
+) -> Result<csharp::Tokens> {
+    let struct_layout = &csharp::import("System.Runtime.InteropServices", "StructLayout");
+    let layout_kind = &csharp::import("System.Runtime.InteropServices", "LayoutKind");
+    let monobehaviour = &csharp::import("UnityEngine", "MonoBehaviour");
+    let component_data = &csharp::import("Unity.Entities", "IComponentData");
+
+    let enums = enums.into_iter().map(|state| {
+        let enum_name = state.name;
+        let authoring_name = format!("{enum_name}{}", config.authoring_suffix);
+        let enum_fields = state.variants.iter();
+
+        let name_list = enum_fields.clone().enumerate().map(|(index, v)| {
+            quote! {
+                $['\r']$(v.as_str()) = $index,
+            }
+        });
+
+        let namespace = config.namespace_for(&state.module);
+        let comp: csharp::Tokens = quote! {
+            namespace $(&namespace)
+            {
+                [$(struct_layout)($layout_kind.Sequential)]
+                public struct $(&enum_name) : $component_data
+                {
+                    public sbyte Value;
+                }
+
+                public class $(&authoring_name) : $monobehaviour
+                {
+                    public enum ENUM_$(&enum_name) : sbyte
+                    {
+                        $(for n in name_list => $n)
+                    }
+
+                    [SerializeField]
+                    public ENUM_$(&enum_name) $(&enum_name);
+
+                    class Baker : Baker<$(&authoring_name)>
+                    {
+                        public override void Bake($(&authoring_name) authoring)
+                        {
+                            var entity = GetEntity(TransformUsageFlags.None);
+                            AddComponent(entity, new $(&enum_name)
+                            {
+                                    Value = (sbyte)authoring.$(&enum_name)
+                            });
+                        }
+                    }
+                }
+            }
+        };
+
+        let state_type = csharp::import(namespace, &enum_name);
+        let file = config.authoring_file(&state.module, &enum_name);
+
+        (comp, enum_name, state_type, file)
+    });
+
+    enums
+        .clone()
+        .try_for_each(|(comp, _, _, file)| files.add(&file, comp))?;
+
+    let enum_types = enums.clone().enumerate().map(|(index, (_, name, _, _))| {
+        quote! {
+            $(&name) = $index
+        }
+    });
+
+    let count = enums.clone().count();
+
+    let generated_comps: csharp::Tokens = quote! {
+        namespace $(&config.namespace)
+        {
+            [$struct_layout($layout_kind.Sequential)]
+            public struct CustomState
+            {
+                public const int CustomStateCount = $count;
+                public CustomStateType ty;
+                public sbyte value;
+            }
+
+            public enum CustomStateType: byte
+            {
+                $(for n in enum_types => $n)
+            }
+        }
+    };
+
+    files.add(&config.states_file, generated_comps)?;
+
+    let add_enums = enums.clone().map(|(_, name, state_type, _)| {
+        quote! {
+            if (manager.HasComponent<$(&state_type)>(entity))
+            {
+                stateArr[stateCount] = new CustomState
+                {
+                    ty = CustomStateType.$(&name),
+                    value = manager.GetComponentData<$(&state_type)>(entity).Value,
+                };
+                stateCount++;
+            }
+
+
+        }
+    });
+
+    Ok(quote! {
+        $(for n in add_enums => $n)
+    })
+}
+
+fn generate_components_csharp(
+    config: &Codegen,
+    structs: Vec<AuthoringStruct>,
+    custom_data: Layout,
+    files: &mut GeneratedFiles,
+) -> Result<csharp::Tokens> {
+    let align = custom_data.align;
+
+    let tags: Vec<bool> = structs.iter().map(AuthoringStruct::is_tag).collect();
+    let structs = structs
+        .into_iter()
+        .map(|item| generate_components_with_authoring(config, item));
+
+    structs
+        .clone()
+        .try_for_each(|(comp, _, _, file, _)| files.add(&file, comp))?;
+
+    let count = structs.clone().count();
+
+    // tags are sent without a value
+    let gen_comps = structs.clone().zip(&tags).filter(|(_, tag)| !**tag).map(
+        |((_, name, comp_type, _, _), _)| {
+            quote! {
+                $['\r'][FieldOffset(0)] public $(&comp_type) $(&name);
+
+            }
+        },
+    );
+
+    let enum_types = structs
+        .clone()
+        .enumerate()
+        .map(|(index, (_, name, _, _, _))| {
+            quote! {
+                $['\r']$name = $index,
+            }
+        });
+
+    let struct_layout = &csharp::import("System.Runtime.InteropServices", "StructLayout");
+    let layout_kind = &csharp::import("System.Runtime.InteropServices", "LayoutKind");
+
+    // C# aligns structs to at most 8 bytes, so with a 16 byte aligned component the union
+    // and its offset in CustomData have to match rust's explicitly
+    let value_offset = custom_data.offsets.get(1).copied();
+    let size = custom_data.size - value_offset.unwrap_or(custom_data.size);
+    let custom_data = match (value_offset, align > 8) {
+        // only tags, C# would make the empty union 1 byte
+        (None, _) => quote! {
+            [$struct_layout($layout_kind.Sequential)]
+            public struct CustomData
+            {
+                public CustomType ty;
+            }
+        },
+        (Some(offset), true) => quote! {
+            [$struct_layout($layout_kind.Explicit, Size = $(custom_data.size))]
+            public struct CustomData
+            {
+                [FieldOffset(0)] public CustomType ty;
+                [FieldOffset($offset)] public CustomComponents value;
+            }
+        },
+        (Some(_), false) => quote! {
+            [$struct_layout($layout_kind.Sequential)]
+            public struct CustomData
+            {
+                public CustomType ty;
+                public CustomComponents value;
+            }
+        },
+    };
+    let components_layout = match align > 8 {
+        true => quote!([$struct_layout($layout_kind.Explicit, Size = $size)]),
+        false => quote!([$struct_layout($layout_kind.Explicit)]),
+    };
+
+    let generated_comps: csharp::Tokens = quote! {
+        namespace $(&config.namespace)
+        {
+            $custom_data
+
+            public enum CustomType : byte
+            {
+                $(for n in enum_types  => $n)
+            }
+
+            $components_layout
+            public struct CustomComponents
+            {
+                public const int ComponentCount = $count;
+                $(for n in gen_comps => $n)
+            }
+        }
+    };
+
+    files.add(&config.components_file, generated_comps)?;
+
+    let unrust_buffer = &csharp::import("unrust.runtime", "UnrustBuffer");
+    let add_comps = structs.clone().zip(&tags).map(|((_, name, comp_type, _, buffers), tag)| {
+        // unity can't read the data of zero sized components
+        if *tag {
+            return quote! {
+
+
+                if (manager.HasComponent<$(&comp_type)>(entity))
+                {
+                    arr[count] = new CustomData { ty = CustomType.$(&name) };
+                    count++;
+                }
+            };
+        }
+
+        if buffers.is_empty() {
+            return quote! {
+
+
+                if (manager.HasComponent<$(&comp_type)>(entity))
+                {
+                    arr[count] = new CustomData
+                    {
+                        ty = CustomType.$(&name),
+                        value = new CustomComponents { $(&name) = manager.GetComponentData<$(&comp_type)>(entity) },
+                    };
+                    count++;
+                }
+            };
+        }
+
+        // the buffers stay valid until the callback returns, nothing changes the entity before then
+        let set_buffers = buffers.iter().map(|(field, element)| {
+            quote! {
+                $['\r']component.$field = $unrust_buffer.From(manager.GetBuffer<$element>(entity, true));
+            }
+        });
+        quote! {
+
+
+            if (manager.HasComponent<$(&comp_type)>(entity))
+            {
+                var component = manager.GetComponentData<$(&comp_type)>(entity);
+                $(for n in set_buffers => $n)
+                arr[count] = new CustomData
+                {
+                    ty = CustomType.$(&name),
+                    value = new CustomComponents { $(&name) = component },
+                };
+                count++;
+            }
+        }
+    });
+
+    let add_comps: csharp::Tokens = quote! {
+        $(for n in add_comps  => $n)
+    };
+
+    Ok(add_comps)
+}
+
+fn generate_components_with_authoring(
+    config: &Codegen,
+    item: AuthoringStruct,
+) -> (
+    csharp::Tokens,
+    String,
+    csharp::Import,
+    String,
+    Vec<(String, csharp::Import)>,
+) {
+    let component_data = &csharp::import("Unity.Entities", "IComponentData");
+    let buffer_element_data = &csharp::import("Unity.Entities", "IBufferElementData");
+    let monobehaviour = &csharp::import("UnityEngine", "MonoBehaviour");
+
+    // element types of the Vec fields, declared next to the component
+    let buffers = buffer_elements(&item);
+    let buffer_structs: Vec<csharp::Tokens> = buffers
+        .iter()
+        .map(|(element, _)| {
+            let (layout, fields) = component_fields(config, element);
+            quote! {
+                $layout
+                public $(unsafe_struct(&element.fields)) $(&element.name) : $buffer_element_data
+                {
+                    $(for n in fields => $n)
+                }
+
+            }
+        })
+        .collect();
+
+    let (component_layout, component_fields) = component_fields(config, &item);
+    let mono_fields = authoring_fields(config, &item, true);
+    let on_validate = on_validate(config, &item);
+    let class_attributes = class_attributes(config, &item.options);
+    let tag = item.is_tag();
+    let (initializers, copies) = bake_fields(&item, "authoring", "component");
+    let transform_usage = item
+        .options
+        .transform
+        .unwrap_or(config.transform_usage)
+        .csharp_name();
+
+    let AuthoringStruct {
+        name: struct_name,
+        module,
+        ..
+    } = item;
+    let namespace = config.namespace_for(&module);
+    let authoring_name = format!("{struct_name}{}", config.authoring_suffix);
+
+    let add_buffers = buffers.iter().map(|(element, field)| {
+        let FieldType::Vec(ty) = &field.ty else {
+            unreachable!()
+        };
+        let value = format!("authoring.{}", field.name);
+        quote! {
+            $['\r']var $(&field.name)Buffer = AddBuffer<$(&element.name)>(entity);
+            if ($(&value) != null)
+            {
+                foreach (var element in $(&value))
+                {
+                    $(&field.name)Buffer.Add(new $(&element.name) { Value = $(bake_value(ty, "element")) });
+                }
+            }
+        }
+    });
+
+    // fixed buffers can only be written to one element at a time, outside the initializer
+    let add_component = match copies.is_empty() {
+        true if tag => quote! {
+            AddComponent<$(&struct_name)>(entity);
+        },
+        true => quote! {
+            AddComponent(entity, new $(&struct_name)
+                {
+                    $(for n in initializers => $n)
+                });
+        },
+        false => quote! {
+            var component = new $(&struct_name)
+            {
+                $(for n in initializers => $n)
+            };
+            unsafe
+            {
+                $(for n in copies => $n)
+            }
+            AddComponent(entity, component);
+        },
+    };
+
+    let tokens: csharp::Tokens = quote! {
+        namespace $(&namespace)
+        {
+            $component_layout
+            public $(unsafe_struct(&item.fields)) $(&struct_name) : $component_data
+            {
+                $(for n in component_fields => $n)
+            }
+
+            $(for n in buffer_structs => $n)
+            $(for n in class_attributes => $n)
+            public class $(&authoring_name) : $monobehaviour
+            {
+                $(for n in mono_fields => $n)
+                $on_validate
+
+                class Baker : Baker<$(&authoring_name)>
+                {
+                    public override void Bake($(&authoring_name) authoring)
+                    {
+                        var entity = GetEntity(TransformUsageFlags.$transform_usage);
+                        $add_component
+                        $(for n in add_buffers => $n)
+                    }
+                }
+            }
+        }
+    };
+
+    let comp_type = csharp::import(&namespace, &struct_name);
+    let file = config.authoring_file(&module, &struct_name);
+    let buffers = buffers
+        .into_iter()
+        .map(|(element, field)| (field.name, csharp::import(&namespace, element.name)))
+        .collect();
+
+    (tokens, struct_name, comp_type, file, buffers)
+}
+
+/// The `IBufferElementData` for every `Vec` field, named after the component and field, with
+/// the element in a field called `Value`.
+fn buffer_elements(item: &AuthoringStruct) -> Vec<(AuthoringStruct, AuthoringField)> {
+    item.fields
+        .iter()
+        .filter_map(|field| match &field.ty {
+            FieldType::Vec(ty) => {
+                let element = AuthoringStruct {
+                    name: format!("{}{}", item.name, pascal_case(&field.name)),
+                    module: item.module.clone(),
+                    fields: vec![AuthoringField {
+                        name: "Value".to_string(),
+                        ty: ty.as_ref().clone(),
+                        inspector: Default::default(),
+                    }],
+                    options: Default::default(),
+                };
+                Some((element, field.clone()))
+            }
+            _ => None,
+        })
+        .collect()
+}
+
+fn pascal_case(name: &str) -> String {
+    name.split('_')
+        .filter(|part| !part.is_empty())
+        .map(|part| {
+            let mut chars = part.chars();
+            chars
+                .next()
+                .map(|first| first.to_uppercase().chain(chars).collect::<String>())
+                .unwrap_or_default()
+        })
+        .collect()
+}
+
+/// `#[unity_struct]`s as a struct laid out like rust for the component, plus a serializable
+/// struct with the inspector types for the authoring MonoBehaviours that converts to it.
+fn generate_structs_csharp(
+    config: &Codegen,
+    structs: &[AuthoringStruct],
+    files: &mut GeneratedFiles,
+) -> Result<()> {
+    let serializable = &csharp::import("System", "Serializable");
+
+    structs.iter().try_for_each(|item| {
+        let (layout, fields) = component_fields(config, item);
+        let inspector_fields = authoring_fields(config, item, false);
+        let (initializers, copies) = bake_fields(item, "this", "value");
+        let authoring_name = format!("{}{}", item.name, config.authoring_suffix);
+
+        let copies = match copies.is_empty() {
+            true => quote!(),
+            false => quote! {
+                unsafe
+                {
+                    $(for n in copies => $n)
+                }
+            },
+        };
+
+        let tokens: csharp::Tokens = quote! {
+            namespace $(config.namespace_for(&item.module))
+            {
+                $layout
+                public $(unsafe_struct(&item.fields)) $(&item.name)
+                {
+                    $(for n in fields => $n)
+                }
+
+                [$serializable]
+                public struct $(&authoring_name)
+                {
+                    $(for n in inspector_fields => $n)
+
+                    public $(&item.name) Bake()
+                    {
+                        var value = new $(&item.name)
+                        {
+                            $(for n in initializers => $n)
+                        };
+                        $copies
+                        return value;
+                    }
+                }
+            }
+        };
+
+        files.add(&config.type_file(&item.module, &item.name), tokens)
+    })
+}
+
+/// `#[unity_config]`s as a struct laid out like rust, plus a ScriptableObject with the
+/// inspector types that exports its values as the bytes of that struct, see `UnrustConfig`.
+fn generate_configs_csharp(
+    config: &Codegen,
+    configs: &[AuthoringStruct],
+    files: &mut GeneratedFiles,
+) -> Result<()> {
+    let unrust_config = &csharp::import("unrust.runtime", "UnrustConfig");
+    let create_asset_menu = &csharp::import("UnityEngine", "CreateAssetMenu");
+
+    configs.iter().try_for_each(|item| {
+        let (layout, fields) = component_fields(config, item);
+        let inspector_fields = authoring_fields(config, item, true);
+        let on_validate = on_validate(config, item);
+        let (initializers, copies) = bake_fields(item, "this", "value");
+        let authoring_name = format!("{}{}", item.name, config.authoring_suffix);
+        // the rust loader is registered for the same extension, see `unrust::UnityConfig`
+        let extension = format!("{}.bytes", snake_case(&item.name));
+        let menu = format!("unrust/{}", item.name);
+
+        let copies = match copies.is_empty() {
+            true => quote!(),
+            false => quote! {
+                unsafe
+                {
+                    $(for n in copies => $n)
+                }
+            },
+        };
+
+        let tokens: csharp::Tokens = quote! {
+            namespace $(config.namespace_for(&item.module))
+            {
+                $layout
+                public $(unsafe_struct(&item.fields)) $(&item.name)
+                {
+                    $(for n in fields => $n)
+                }
+
+                [$create_asset_menu(fileName = $(quoted(&item.name)), menuName = $(quoted(menu)))]
+                public class $(&authoring_name) : $unrust_config
+                {
+                    $(for n in inspector_fields => $n)
+                    $on_validate
+
+                    public override string Extension => $(quoted(extension));
+
+                    public override byte[] Export()
+                    {
+                        var value = new $(&item.name)
+                        {
+                            $(for n in initializers => $n)
+                        };
+                        $copies
+                        return ToBytes(value);
+                    }
+                }
+            }
+        };
+
+        files.add(&config.authoring_file(&item.module, &item.name), tokens)
+    })
+}
+
+/// `LevelSettings` -> `level_settings`, the same as `#[unity_config]` does in rust.
+fn snake_case(name: &str) -> String {
+    let mut snake = String::new();
+    let mut previous_lower = false;
+    for c in name.chars() {
+        if c.is_uppercase() && previous_lower {
+            snake.push('_');
+        }
+        previous_lower = c.is_lowercase() || c.is_ascii_digit();
+        snake.extend(c.to_lowercase());
+    }
+
+    snake
+}
+
+/// `struct`, or `unsafe struct` if a field is a fixed buffer.
+fn unsafe_struct(fields: &[AuthoringField]) -> &'static str {
+    match fields
+        .iter()
+        .any(|field| matches!(field.ty, FieldType::Array(..)))
+    {
+        true => "unsafe struct",
+        false => "struct",
+    }
+}
+
+/// The `StructLayout` and fields of the C# struct that matches the rust layout.
+fn component_fields(
+    config: &Codegen,
+    item: &AuthoringStruct,
+) -> (csharp::Tokens, Vec<csharp::Tokens>) {
+    let struct_layout = &csharp::import("System.Runtime.InteropServices", "StructLayout");
+    let layout_kind = &csharp::import("System.Runtime.InteropServices", "LayoutKind");
+    let marshal_as = &csharp::import("System.Runtime.InteropServices", "MarshalAs");
+    let unmanaged_type = &csharp::import("System.Runtime.InteropServices", "UnmanagedType");
+
+    // glam's simd types are 16 byte aligned in rust but not in C#, so place every field by hand
+    let layout = item.layout();
+    let explicit = layout.align > 8;
+    let struct_layout = match explicit {
+        true => quote!([$struct_layout($layout_kind.Explicit, Size = $(layout.size))]),
+        false => quote!([$struct_layout($layout_kind.Sequential)]),
+    };
+
+    let fields = item
+        .fields
+        .iter()
+        .zip(&layout.offsets)
+        .map(|(field, offset)| {
+            let offset = match explicit {
+                true => quote!([FieldOffset($(*offset))]$[' ']),
+                false => quote!(),
+            };
+            match &field.ty {
+                // C# marshals bool as 4 bytes by default, rust's is 1
+                FieldType::Bool => quote! {
+                    $['\r']$(offset)[$marshal_as($unmanaged_type.U1)] public bool $(&field.name);
+                },
+                FieldType::Array(ty, len) => quote! {
+                    $['\r']$(offset)public fixed $(fixed_buffer_type(ty)) $(&field.name)[$(*len)];
+                },
+                _ => quote! {
+                    $['\r']$(offset)public $(component_type(config, &field.ty)) $(&field.name);
+                },
+            }
+        })
+        .collect();
+
+    (struct_layout, fields)
+}
+
+/// The `[RequireComponent]` and `[AddComponentMenu]` attributes of an authoring MonoBehaviour.
+fn class_attributes(config: &Codegen, options: &AuthoringOptions) -> Vec<csharp::Tokens> {
+    let mut attributes: Vec<csharp::Tokens> = options
+        .requires
+        .iter()
+        .map(|required| {
+            let attribute = csharp::import("UnityEngine", "RequireComponent");
+            let name = format!("{}{}", required.name, config.authoring_suffix);
+            let class = csharp::import(config.namespace_for(&required.module), name);
+            quote!($['\r'][$attribute(typeof($class))])
+        })
+        .collect();
+    if let Some(menu) = &options.menu {
+        let attribute = csharp::import("UnityEngine", "AddComponentMenu");
+        attributes.push(quote!($['\r'][$attribute($(csharp_string(menu)))]));
+    }
+
+    attributes
+}
+
+/// Fields with the inspector types and attributes. Fields on MonoBehaviours start at their
+/// default and arrays at their rust length, serializable structs can't have initializers.
+fn authoring_fields(
+    config: &Codegen,
+    item: &AuthoringStruct,
+    initialize: bool,
+) -> Vec<csharp::Tokens> {
+    item.fields
+        .iter()
+        .map(|field| {
+            let attributes = inspector_attributes(&field.inspector);
+            let ty = csharp_authoring_type(config, &field.ty);
+            let initializer = match (&field.ty, &field.inspector.default) {
+                (FieldType::Array(element, len), _) if initialize => Some(quote! {
+                    new $(csharp_authoring_type(config, element))[$(*len)]
+                }),
+                (_, Some(default)) if initialize => {
+                    Some(default_initializer(config, &field.ty, default))
+                }
+                _ => None,
+            };
+
+            match initializer {
+                Some(initializer) => quote! {
+                    $(for a in attributes => $['\r']$a)
+                    $['\r']public $ty $(&field.name) = $initializer;
+                },
+                None => quote! {
+                    $(for a in attributes => $['\r']$a)
+                    $['\r']public $ty $(&field.name);
+                },
+            }
+        })
+        .collect()
+}
+
+/// The `[Header]`, `[Tooltip]` and `[Range]` attributes of a field.
+fn inspector_attributes(inspector: &Inspector) -> Vec<csharp::Tokens> {
+    let mut attributes = vec![];
+    if let Some(header) = &inspector.header {
+        let attribute = csharp::import("UnityEngine", "Header");
+        attributes.push(quote!([$attribute($(csharp_string(header)))]));
+    }
+    if let Some(tooltip) = &inspector.tooltip {
+        let attribute = csharp::import("UnityEngine", "Tooltip");
+        attributes.push(quote!([$attribute($(csharp_string(tooltip)))]));
+    }
+    if let Some((min, max)) = &inspector.range {
+        let attribute = csharp::import("UnityEngine", "Range");
+        attributes.push(quote!([$attribute($(min)f, $(max)f)]));
+    }
+
+    attributes
+}
+
+/// The C# expression a MonoBehaviour field starts at.
+fn default_initializer(config: &Codegen, ty: &FieldType, default: &DefaultValue) -> csharp::Tokens {
+    match default {
+        DefaultValue::Number(value) => number_literal(ty, value),
+        DefaultValue::Bool(value) => quote!($(value.to_string())),
+        DefaultValue::Char(value) => {
+            let escaped = csharp_string(&value.to_string());
+            quote!($(format!("'{}'", &escaped[1..escaped.len() - 1])))
+        }
+        DefaultValue::String(value) => quote!($(csharp_string(value))),
+        DefaultValue::Floats(values) => {
+            let values = values
+                .iter()
+                .map(|value| format!("{value}f"))
+                .collect::<Vec<_>>();
+            quote!(new $(csharp_authoring_type(config, ty))($(values.join(", "))))
+        }
+        DefaultValue::Variant(variant) => quote!($(csharp_authoring_type(config, ty)).$variant),
+    }
+}
+
+/// A number as a C# literal of `ty`.
+fn number_literal(ty: &FieldType, value: &str) -> csharp::Tokens {
+    match ty {
+        FieldType::F32 => quote!($(value)f),
+        FieldType::F64 => quote!($(value)d),
+        _ => quote!($value),
+    }
+}
+
+/// A C# string literal, escaping anything outside printable ascii.
+fn csharp_string(value: &str) -> String {
+    let mut literal = String::from("\"");
+    for c in value.chars() {
+        match c {
+            '"' => literal.push_str("\\\""),
+            '\'' => literal.push_str("\\'"),
+            '\\' => literal.push_str("\\\\"),
+            ' '..='~' => literal.push(c),
+            _ => {
+                let mut units = [0; 2];
+                for unit in c.encode_utf16(&mut units) {
+                    literal.push_str(&format!("\\u{unit:04X}"));
+                }
+            }
+        }
+    }
+    literal.push('"');
+
+    literal
+}
+
+/// Warns about fields breaking their `#[validate(..)]` rules while they are edited.
+fn on_validate(config: &Codegen, item: &AuthoringStruct) -> Option<csharp::Tokens> {
+    let debug = &csharp::import("UnityEngine", "Debug");
+    let array = &csharp::import("System", "Array");
+    let authoring_name = format!("{}{}", item.name, config.authoring_suffix);
+
+    let checks: Vec<csharp::Tokens> = item
+        .fields
+        .iter()
+        .flat_map(|field| {
+            field
+                .inspector
+                .validation
+                .iter()
+                .map(move |rule| (field, rule))
+        })
+        .map(|(field, rule)| {
+            let name = &field.name;
+            let condition = match (&field.ty, rule) {
+                (FieldType::String, Validation::NonEmpty) => quote!(string.IsNullOrEmpty($name)),
+                (_, Validation::NonEmpty) => quote!($name == null || $name.Length == 0),
+                // the other rules apply to every element
+                (FieldType::Array(ty, _) | FieldType::Vec(ty), _) => quote! {
+                    $name != null && $array.Exists($name, value => $(broken_rule(config, ty, rule, "value")))
+                },
+                (ty, _) => broken_rule(config, ty, rule, name),
+            };
+            let message = match rule {
+                Validation::Min(min) => format!("should be at least {min}"),
+                Validation::Max(max) => format!("should be at most {max}"),
+                Validation::NonZero => "should not be zero".to_string(),
+                Validation::NonEmpty => "should not be empty".to_string(),
+            };
+            let message = csharp_string(&format!("{authoring_name}.{name} {message}"));
+
+            quote! {
+                $['\r']if ($condition)
+                {
+                    $debug.LogWarning($message, this);
+                }
+            }
+        })
+        .collect();
+
+    if checks.is_empty() {
+        return None;
+    }
+
+    Some(quote! {
+
+        void OnValidate()
+        {
+            $(for n in checks => $n)
+        }
+    })
+}
+
+/// The C# condition for a `value` of `ty` breaking a number or vector `rule`.
+fn broken_rule(config: &Codegen, ty: &FieldType, rule: &Validation, value: &str) -> csharp::Tokens {
+    match rule {
+        Validation::Min(min) => quote!($value < $(number_literal(ty, min))),
+        Validation::Max(max) => quote!($value > $(number_literal(ty, max))),
+        Validation::NonZero => match ty {
+            FieldType::Vec2 | FieldType::Vec3 | FieldType::Vec4 => {
+                quote!($value == $(csharp_authoring_type(config, ty)).zero)
+            }
+            _ => quote!($value == 0),
+        },
+        Validation::NonEmpty => unreachable!("only strings and Vecs can be non_empty"),
+    }
+}
+
+/// The object initializer entries converting `source` to the component, and the statements
+/// copying arrays into the fixed buffers of `target` afterwards, at most the rust length.
+fn bake_fields(
+    item: &AuthoringStruct,
+    source: &str,
+    target: &str,
+) -> (Vec<csharp::Tokens>, Vec<csharp::Tokens>) {
+    let mut initializers = vec![];
+    let mut copies = vec![];
+
+    for field in &item.fields {
+        let value = format!("{source}.{}", field.name);
+        match &field.ty {
+            FieldType::Array(ty, len) => {
+                let element = bake_value(ty, &format!("{value}[i]"));
+                let element = match ty.as_ref() {
+                    FieldType::Enum(_) => format!("(byte){element}"),
+                    _ => element,
+                };
+                copies.push(quote! {
+                    $['\r']if ($(&value) != null)
+                    {
+                        for (var i = 0; i < $(*len) && i < $(&value).Length; i++)
+                        {
+                            $target.$(&field.name)[i] = $element;
+                        }
+                    }
+                });
+            }
+            // baked into a DynamicBuffer, the hooks point the field at it when spawning
+            FieldType::Vec(_) => {}
+            _ => initializers.push(quote! {
+                $['\r']$(&field.name) = $(bake_value(&field.ty, &value)),
+            }),
+        }
+    }
+
+    (initializers, copies)
+}
+
+/// C# only allows primitives in fixed buffers, enums are stored as their byte.
+fn fixed_buffer_type(ty: &FieldType) -> &'static str {
+    match ty {
+        FieldType::Bool => "bool",
+        FieldType::U8 | FieldType::Enum(_) => "byte",
+        FieldType::I8 => "sbyte",
+        FieldType::U16 => "ushort",
+        FieldType::I16 => "short",
+        FieldType::F32 => "float",
+        FieldType::F64 => "double",
+        FieldType::I32 => "int",
+        FieldType::I64 => "long",
+        FieldType::U32 | FieldType::Char => "uint",
+        FieldType::U64 => "ulong",
+        // rejected when discovering the fields, see FieldType::fits_fixed_buffer
+        _ => unreachable!("{ty:?} can not be in a fixed buffer"),
+    }
+}
+
+/// The type used in the `IComponentData`, which has to match the rust layout.
+pub(crate) fn csharp_type(config: &Codegen, ty: &FieldType) -> String {
+    let name = match ty {
+        FieldType::Bool => "bool",
+        FieldType::U8 => "byte",
+        FieldType::I8 => "sbyte",
+        FieldType::U16 => "ushort",
+        FieldType::I16 => "short",
+        FieldType::F32 => "float",
+        FieldType::F64 => "double",
+        FieldType::I32 => "int",
+        FieldType::I64 => "long",
+        FieldType::U32 => "uint",
+        FieldType::U64 => "ulong",
+        // a rust char is a 4 byte unicode scalar value, a C# char is a 2 byte utf-16 unit
+        FieldType::Char => "uint",
+        FieldType::Vec2 => "float2",
+        FieldType::Vec3 => "float3",
+        FieldType::Vec4 => "float4",
+        FieldType::Quat => "quaternion",
+        FieldType::Mat4 => "float4x4",
+        FieldType::Color => "float4",
+        FieldType::String => "Unity.Collections.FixedString64Bytes",
+        FieldType::EntityRef => "Unity.Entities.Entity",
+        FieldType::AssetPath | FieldType::Handle(_) => "Unity.Collections.FixedString128Bytes",
+        FieldType::Curve => "unrust.runtime.UnrustCurve",
+        FieldType::Gradient => "unrust.runtime.UnrustGradient",
+        FieldType::Enum(AuthoringEnum { name, module, .. })
+        | FieldType::Struct(AuthoringStruct { name, module, .. }) => {
+            return format!("{}.{name}", config.namespace_for(module))
+        }
+        FieldType::Array(ty, len) => return format!("{}[{len}]", csharp_type(config, ty)),
+        FieldType::Vec(_) => "unrust.runtime.UnrustBuffer",
+    };
+
+    name.to_string()
+}
+
+/// [`csharp_type`] with the `using` for types outside the System namespace.
+fn component_type(config: &Codegen, ty: &FieldType) -> csharp::Tokens {
+    match ty {
+        FieldType::Vec2
+        | FieldType::Vec3
+        | FieldType::Vec4
+        | FieldType::Quat
+        | FieldType::Mat4
+        | FieldType::Color => {
+            quote!($(csharp::import("Unity.Mathematics", csharp_type(config, ty))))
+        }
+        FieldType::Enum(AuthoringEnum { name, module, .. })
+        | FieldType::Struct(AuthoringStruct { name, module, .. }) => {
+            quote!($(csharp::import(config.namespace_for(module), name)))
+        }
+        FieldType::String => quote!($(csharp::import("Unity.Collections", "FixedString64Bytes"))),
+        FieldType::EntityRef => quote!($(csharp::import("Unity.Entities", "Entity"))),
+        FieldType::AssetPath | FieldType::Handle(_) => {
+            quote!($(csharp::import("Unity.Collections", "FixedString128Bytes")))
+        }
+        FieldType::Vec(_) => quote!($(csharp::import("unrust.runtime", "UnrustBuffer"))),
+        FieldType::Curve => quote!($(csharp::import("unrust.runtime", "UnrustCurve"))),
+        FieldType::Gradient => quote!($(csharp::import("unrust.runtime", "UnrustGradient"))),
+        _ => quote!($(csharp_type(config, ty))),
+    }
+}
+
+/// The type shown in the inspector on the authoring MonoBehaviour.
+fn csharp_authoring_type(config: &Codegen, ty: &FieldType) -> csharp::Tokens {
+    let name = match ty {
+        FieldType::Char => "char",
+        FieldType::Vec2 => "Vector2",
+        FieldType::Vec3 => "Vector3",
+        FieldType::Vec4 => "Vector4",
+        FieldType::Quat => "Quaternion",
+        FieldType::Mat4 => "Matrix4x4",
+        FieldType::Color => "Color",
+        FieldType::String => "string",
+        FieldType::EntityRef => return quote!($(csharp::import("UnityEngine", "GameObject"))),
+        // any asset, UnityEngine.Object is ambiguous with System.Object
+        FieldType::AssetPath | FieldType::Handle(_) => "UnityEngine.Object",
+        FieldType::Curve => return quote!($(csharp::import("UnityEngine", "AnimationCurve"))),
+        FieldType::Gradient => return quote!($(csharp::import("UnityEngine", "Gradient"))),
+        FieldType::Struct(item) => {
+            let name = format!("{}{}", item.name, config.authoring_suffix);
+            return quote!($(csharp::import(config.namespace_for(&item.module), name)));
+        }
+        FieldType::Array(ty, _) | FieldType::Vec(ty) => {
+            return quote!($(csharp_authoring_type(config, ty))[])
+        }
+        _ => return component_type(config, ty),
+    };
+
+    quote!($name)
+}
+
+/// Converts an authoring field to its component value inside the Baker.
+fn bake_value(ty: &FieldType, value: &str) -> String {
+    match ty {
+        // lone surrogates are not valid rust chars, send the replacement character instead
+        FieldType::Char => {
+            format!("char.IsSurrogate({value}) ? 0xFFFDu : (uint){value}")
+        }
+        // the UnityEngine types convert implicitly, except Color
+        FieldType::Color => {
+            format!("new float4({value}.r, {value}.g, {value}.b, {value}.a)")
+        }
+        // longer strings are cut at a character boundary, with a warning
+        FieldType::String => format!("unrust.runtime.UnrustString.From({value})"),
+        // the referenced object is baked on its own, this only records which entity it became
+        FieldType::EntityRef => {
+            format!("{value} != null ? GetEntity({value}, TransformUsageFlags.None) : Entity.Null")
+        }
+        FieldType::AssetPath | FieldType::Handle(_) => {
+            format!("unrust.runtime.UnrustAssetPath.From({value})")
+        }
+        FieldType::Curve => format!("unrust.runtime.UnrustCurve.From({value})"),
+        FieldType::Gradient => format!("unrust.runtime.UnrustGradient.From({value})"),
+        FieldType::Struct(_) => format!("{value}.Bake()"),
+        _ => value.to_string(),
+    }
+}
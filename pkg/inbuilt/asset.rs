@@ -0,0 +1,48 @@
+use bevy::asset::{Asset, AssetPath, AssetServer, Handle};
+use std::mem::{align_of, size_of};
+
+use crate::UnityField;
+
+/// A `Handle<T>` or `AssetPath` field, the path of the asset relative to the unity `Assets`
+/// folder, laid out like unity's `FixedString128Bytes`. Empty when nothing was assigned.
+#[derive(Clone, Copy)]
+#[repr(C)]
+pub struct UnityAssetPath {
+    pub len: u16,
+    pub bytes: [u8; 126],
+}
+
+const _: () = assert!(size_of::<UnityAssetPath>() == 128 && align_of::<UnityAssetPath>() == 2);
+
+impl UnityAssetPath {
+    fn as_str(&self) -> Option<&str> {
+        let bytes = self.bytes.get(..self.len as usize)?;
+        std::str::from_utf8(bytes).ok()
+    }
+}
+
+impl UnityField for AssetPath<'static> {
+    type Wire = UnityAssetPath;
+
+    fn from_wire(wire: Self::Wire, _: Option<&AssetServer>) -> Option<Self> {
+        Some(AssetPath::from(wire.as_str()?.to_owned()))
+    }
+}
+
+impl<T: Asset> UnityField for Handle<T> {
+    type Wire = UnityAssetPath;
+
+    fn from_wire(wire: Self::Wire, assets: Option<&AssetServer>) -> Option<Self> {
+        let path = wire.as_str()?;
+        if path.is_empty() {
+            return Some(Handle::default());
+        }
+
+        let Some(assets) = assets else {
+            tracing::warn!("can not load {path}, the world has no AssetServer");
+            return None;
+        };
+
+        Some(assets.load(path.to_owned()))
+    }
+}
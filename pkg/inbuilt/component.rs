@@ -0,0 +1,166 @@
+use bevy::asset::AssetServer;
+use bevy::prelude::{Mat4, Quat, Vec2, Vec3, Vec4};
+use std::mem::{align_of, size_of};
+
+use crate::{UnityCurve, UnityEntityRef, UnityGradient, UnityValidationError};
+
+/// Implemented by `#[unity_authoring]` for every custom component. Unity sends the component
+/// as the bytes of [`UnityComponent::Wire`], a `#[repr(C)]` struct with the
+/// [`UnityField::Wire`] of every field. `#[unity_config]`s are exported to files the same way.
+pub trait UnityComponent: Sized {
+    /// The `#[repr(C)]` struct the generated C# component matches.
+    type Wire: Copy;
+
+    /// `None` if any field holds a value its rust type cannot represent. `Handle<T>` fields
+    /// are loaded with `assets`, they fail to convert without one.
+    fn from_wire(wire: &Self::Wire, assets: Option<&AssetServer>) -> Option<Self>;
+
+    /// Every [`UnityEntityRef`] in the component with the field holding it, to resolve them
+    /// once their entities spawn.
+    fn entity_refs(&mut self) -> Vec<(&'static str, &mut UnityEntityRef)> {
+        vec![]
+    }
+
+    /// The fields breaking their `#[validate(..)]` rules.
+    fn validate(&self) -> Vec<UnityValidationError> {
+        vec![]
+    }
+}
+
+/// A type that can be a field of a custom component. The wire type is plain bytes that any
+/// value from C# is valid for, so types with invalid bit patterns (`bool`, `char`, enums)
+/// are checked here instead of being transmuted.
+pub trait UnityField: Sized {
+    type Wire: Copy;
+
+    fn from_wire(wire: Self::Wire, assets: Option<&AssetServer>) -> Option<Self>;
+
+    /// Adds the [`UnityEntityRef`]s inside the value to `refs`.
+    fn entity_refs<'a>(&'a mut self, _refs: &mut Vec<&'a mut UnityEntityRef>) {}
+}
+
+macro_rules! same_on_wire {
+    ($($ty:ty),*) => {
+        $(
+            impl UnityField for $ty {
+                type Wire = $ty;
+
+                fn from_wire(wire: Self::Wire, _: Option<&AssetServer>) -> Option<Self> {
+                    Some(wire)
+                }
+            }
+        )*
+    };
+}
+
+same_on_wire!(u8, i8, u16, i16, u32, i32, u64, i64, f32, f64, Vec2, Vec3, Vec4, Quat, Mat4);
+same_on_wire!(UnityCurve, UnityGradient);
+
+impl UnityField for bool {
+    type Wire = u8;
+
+    fn from_wire(wire: Self::Wire, _: Option<&AssetServer>) -> Option<Self> {
+        match wire {
+            0 => Some(false),
+            1 => Some(true),
+            _ => None,
+        }
+    }
+}
+
+impl UnityField for char {
+    type Wire = u32;
+
+    fn from_wire(wire: Self::Wire, _: Option<&AssetServer>) -> Option<Self> {
+        char::from_u32(wire)
+    }
+}
+
+/// Unity's `Color` as srgb rgba. Needs unrust's `bevy_render` feature, bevy's default features
+/// enable it in games that render anyway.
+#[cfg(feature = "bevy_render")]
+impl UnityField for bevy::render::color::Color {
+    type Wire = [f32; 4];
+
+    fn from_wire(wire: Self::Wire, _: Option<&AssetServer>) -> Option<Self> {
+        Some(bevy::render::color::Color::rgba(
+            wire[0], wire[1], wire[2], wire[3],
+        ))
+    }
+}
+
+impl<T: UnityField, const N: usize> UnityField for [T; N] {
+    type Wire = [T::Wire; N];
+
+    fn from_wire(wire: Self::Wire, assets: Option<&AssetServer>) -> Option<Self> {
+        let values = wire.map(|wire| T::from_wire(wire, assets));
+        if values.iter().any(Option::is_none) {
+            return None;
+        }
+
+        Some(values.map(Option::unwrap))
+    }
+
+    fn entity_refs<'a>(&'a mut self, refs: &mut Vec<&'a mut UnityEntityRef>) {
+        self.iter_mut().for_each(|value| value.entity_refs(refs));
+    }
+}
+
+/// The contents of a unity `DynamicBuffer`, which is how a `Vec` field is sent. The
+/// generated hooks point it at the entity's buffer right before calling into rust.
+#[derive(Clone, Copy)]
+#[repr(C)]
+pub struct UnityBuffer<T> {
+    pub ptr: *const T,
+    pub len: usize,
+}
+
+impl<T: UnityField> UnityField for Vec<T> {
+    type Wire = UnityBuffer<T::Wire>;
+
+    fn from_wire(wire: Self::Wire, assets: Option<&AssetServer>) -> Option<Self> {
+        if wire.len == 0 || wire.ptr.is_null() {
+            return Some(vec![]);
+        }
+
+        // SAFETY: unity only sends these while spawning, pointing at `len` elements of the
+        // entity's buffer. Chunk memory has no alignment guarantee for the glam types.
+        (0..wire.len)
+            .map(|index| T::from_wire(unsafe { wire.ptr.add(index).read_unaligned() }, assets))
+            .collect()
+    }
+
+    fn entity_refs<'a>(&'a mut self, refs: &mut Vec<&'a mut UnityEntityRef>) {
+        self.iter_mut().for_each(|value| value.entity_refs(refs));
+    }
+}
+
+/// A `String` field, laid out like unity's `FixedString64Bytes`: the utf-8 length followed
+/// by up to 61 bytes and a terminating zero.
+#[derive(Clone, Copy)]
+#[repr(C)]
+pub struct UnityString {
+    pub len: u16,
+    pub bytes: [u8; 62],
+}
+
+const _: () = assert!(size_of::<UnityString>() == 64 && align_of::<UnityString>() == 2);
+
+impl UnityField for String {
+    type Wire = UnityString;
+
+    fn from_wire(wire: Self::Wire, _: Option<&AssetServer>) -> Option<Self> {
+        let bytes = wire.bytes.get(..wire.len as usize)?;
+        std::str::from_utf8(bytes).ok().map(str::to_owned)
+    }
+}
+
+// codegen lays the glam types out with these sizes and alignments (16 unless the
+// `scalar-math` feature is enabled), so building with anything else has to fail here
+const _: () = {
+    assert!(size_of::<Vec2>() == 8 && align_of::<Vec2>() == 4);
+    assert!(size_of::<Vec3>() == 12 && align_of::<Vec3>() == 4);
+    assert!(size_of::<Vec4>() == 16 && align_of::<Vec4>() == 16);
+    assert!(size_of::<Quat>() == 16 && align_of::<Quat>() == 16);
+    assert!(size_of::<Mat4>() == 64 && align_of::<Mat4>() == 16);
+};
@@ -0,0 +1,94 @@
+use bevy::asset::{AddAsset, Asset, AssetLoader, AssetServer, LoadContext, LoadedAsset};
+use bevy::prelude::{App, Plugin};
+use bevy::utils::BoxedFuture;
+use std::marker::PhantomData;
+use std::mem::size_of;
+
+use crate::UnityComponent;
+
+/// Implemented by `#[unity_config]`. Unity exports the config's ScriptableObject next to its
+/// `.asset` as the bytes of [`UnityComponent::Wire`], eg: `Level1.level_settings.bytes`,
+/// which [`UnityConfigPlugin`] loads as an asset of this type.
+pub trait UnityConfig: UnityComponent + Asset {
+    /// The snake case name of the type followed by `.bytes`.
+    const EXTENSION: &'static str;
+}
+
+/// Registers `T` as an asset along with its [`UnityConfigLoader`]. Added by `unrust_setup`
+/// for every type in its `configs = [..]` section.
+pub struct UnityConfigPlugin<T>(PhantomData<fn() -> T>);
+
+impl<T> Default for UnityConfigPlugin<T> {
+    fn default() -> Self {
+        UnityConfigPlugin(PhantomData)
+    }
+}
+
+impl<T: UnityConfig> Plugin for UnityConfigPlugin<T> {
+    fn build(&self, app: &mut App) {
+        let assets = app.world.resource::<AssetServer>().clone();
+        app.add_asset::<T>()
+            .add_asset_loader(UnityConfigLoader::<T> {
+                assets,
+                marker: PhantomData,
+            });
+    }
+}
+
+/// Loads the files exported for a [`UnityConfig`]. They are reloaded whenever unity exports
+/// them again, like every other asset.
+pub struct UnityConfigLoader<T> {
+    /// Loads the config's `Handle<T>` fields.
+    assets: AssetServer,
+    marker: PhantomData<fn() -> T>,
+}
+
+impl<T: UnityConfig> AssetLoader for UnityConfigLoader<T> {
+    fn load<'a>(
+        &'a self,
+        bytes: &'a [u8],
+        load_context: &'a mut LoadContext,
+    ) -> BoxedFuture<'a, Result<(), bevy::asset::Error>> {
+        Box::pin(async move {
+            let config = config_from_bytes::<T>(bytes, &self.assets)?;
+            load_context.set_default_asset(LoadedAsset::new(config));
+            Ok(())
+        })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        std::slice::from_ref(&T::EXTENSION)
+    }
+}
+
+fn config_from_bytes<T: UnityConfig>(bytes: &[u8], assets: &AssetServer) -> anyhow::Result<T> {
+    let name = std::any::type_name::<T>()
+        .rsplit("::")
+        .next()
+        .unwrap_or_default();
+    let size = size_of::<T::Wire>();
+    if bytes.len() != size {
+        anyhow::bail!(
+            "{name} is {size} bytes in rust but the file has {}, rerun the unrust codegen and export it again",
+            bytes.len()
+        );
+    }
+
+    // SAFETY: the length matches and any bytes are a valid wire struct, see `UnityField`.
+    // the file has no alignment guarantee for the glam types
+    let wire = unsafe { bytes.as_ptr().cast::<T::Wire>().read_unaligned() };
+    let Some(config) = T::from_wire(&wire, Some(assets)) else {
+        anyhow::bail!("{name} has field values rust can not represent");
+    };
+
+    let errors = config.validate();
+    if !errors.is_empty() {
+        let errors = errors
+            .iter()
+            .map(|error| format!("`{name}.{}` {}", error.field, error.message))
+            .collect::<Vec<_>>();
+        anyhow::bail!("{}", errors.join(", "));
+    }
+
+    Ok(config)
+}
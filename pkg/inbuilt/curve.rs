@@ -0,0 +1,66 @@
+use bevy::prelude::Vec4;
+use std::mem::{align_of, size_of};
+
+/// A unity `AnimationCurve`, sampled at evenly spaced times between its first and last key
+/// when baked. Laid out like `unrust.runtime.UnrustCurve`.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+#[repr(C)]
+pub struct UnityCurve {
+    /// Time of the first key.
+    pub start: f32,
+    /// Time of the last key.
+    pub end: f32,
+    pub samples: [f32; UnityCurve::SAMPLES],
+}
+
+impl UnityCurve {
+    pub const SAMPLES: usize = 32;
+
+    /// The value at `t`, linearly interpolated between the samples. Times outside the keys
+    /// are clamped, the curve's wrap modes are not baked.
+    pub fn evaluate(&self, t: f32) -> f32 {
+        let span = self.end - self.start;
+        if span <= 0.0 {
+            return self.samples[0];
+        }
+
+        let (index, fraction) = sample_position((t - self.start) / span, Self::SAMPLES);
+        let (from, to) = (self.samples[index], self.samples[index + 1]);
+        from + (to - from) * fraction
+    }
+}
+
+/// A unity `Gradient`, sampled at evenly spaced times from 0 to 1 when baked. Laid out like
+/// `unrust.runtime.UnrustGradient`.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+#[repr(C)]
+pub struct UnityGradient {
+    /// srgb rgba colors.
+    pub samples: [[f32; 4]; UnityGradient::SAMPLES],
+}
+
+impl UnityGradient {
+    pub const SAMPLES: usize = 16;
+
+    /// The srgb rgba color at `t` in `0..=1`, linearly interpolated between the samples.
+    /// Use `Color::rgba` to turn it into a bevy color.
+    pub fn evaluate(&self, t: f32) -> Vec4 {
+        let (index, fraction) = sample_position(t, Self::SAMPLES);
+        let from = Vec4::from_array(self.samples[index]);
+        let to = Vec4::from_array(self.samples[index + 1]);
+        from.lerp(to, fraction)
+    }
+}
+
+/// The sample before `t` in `0..=1` and how far `t` is towards the next one.
+fn sample_position(t: f32, samples: usize) -> (usize, f32) {
+    let position = t.clamp(0.0, 1.0) * (samples - 1) as f32;
+    let index = (position as usize).min(samples - 2);
+    (index, position - index as f32)
+}
+
+// codegen lays these out with the same sizes
+const _: () = {
+    assert!(size_of::<UnityCurve>() == 136 && align_of::<UnityCurve>() == 4);
+    assert!(size_of::<UnityGradient>() == 256 && align_of::<UnityGradient>() == 4);
+};
@@ -1,7 +1,9 @@
+use crate::FieldSchema;
 use genco::prelude::*;
-use unrust_proc_macro::unity_authoring;
+use std::mem::offset_of;
 
-#[unity_authoring]
+#[derive(bevy::prelude::Component, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[repr(C)]
 pub struct UnityEntity {
     pub index: i32,
     pub version: i32,
@@ -34,3 +36,23 @@ pub fn UnityEntity_CSHARP_TOKEN() -> csharp::Tokens {
         }
     }
 }
+
+#[allow(non_snake_case)]
+pub fn UnityEntity_SCHEMA_FIELDS() -> Vec<FieldSchema> {
+    vec![
+        FieldSchema::new::<i32>(
+            "index",
+            "Index",
+            "i32",
+            "int",
+            offset_of!(UnityEntity, index),
+        ),
+        FieldSchema::new::<i32>(
+            "version",
+            "Version",
+            "i32",
+            "int",
+            offset_of!(UnityEntity, version),
+        ),
+    ]
+}
@@ -0,0 +1,171 @@
+use bevy::ecs::world::EntityMut;
+use bevy::prelude::*;
+use bevy::utils::HashMap;
+use std::marker::PhantomData;
+
+use crate::validate::is_valid;
+use crate::{UnityComponent, UnityEntity, UnityField};
+
+/// A field pointing at another GameObject, shown as a `GameObject` slot in the inspector.
+/// Unity sends the baked entity, which becomes the bevy entity once that one has spawned too.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum UnityEntityRef {
+    /// Nothing was assigned in the inspector.
+    #[default]
+    None,
+    /// The referenced entity has not been spawned in bevy yet.
+    Pending(UnityEntity),
+    Resolved(Entity),
+}
+
+impl UnityEntityRef {
+    /// The bevy entity, once it exists.
+    pub fn entity(&self) -> Option<Entity> {
+        match self {
+            UnityEntityRef::Resolved(entity) => Some(*entity),
+            _ => None,
+        }
+    }
+
+    pub fn is_pending(&self) -> bool {
+        matches!(self, UnityEntityRef::Pending(_))
+    }
+}
+
+impl UnityField for UnityEntityRef {
+    type Wire = UnityEntity;
+
+    fn from_wire(wire: Self::Wire, _: Option<&AssetServer>) -> Option<Self> {
+        // Entity.Null in unity
+        if wire.index == 0 && wire.version == 0 {
+            return Some(UnityEntityRef::None);
+        }
+
+        Some(UnityEntityRef::Pending(wire))
+    }
+
+    fn entity_refs<'a>(&'a mut self, refs: &mut Vec<&'a mut UnityEntityRef>) {
+        refs.push(self);
+    }
+}
+
+/// How many frames a reference waits for its entity before it is given up on. A reference to
+/// a GameObject that is disabled, stripped or in another subscene never spawns.
+pub const MAX_PENDING_FRAMES: u32 = 120;
+
+/// Marks a `T` with entity references that have not been resolved yet, see [`resolve_entity_refs`].
+#[derive(Component)]
+pub struct PendingEntityRefs<T: Send + Sync + 'static> {
+    frames: u32,
+    marker: PhantomData<T>,
+}
+
+impl<T: Send + Sync + 'static> Default for PendingEntityRefs<T> {
+    fn default() -> Self {
+        Self {
+            frames: 0,
+            marker: PhantomData,
+        }
+    }
+}
+
+/// The bevy entity spawned for every [`UnityEntity`], kept up to date by [`index_unity_entities`].
+#[derive(Resource, Default)]
+pub struct UnityEntityIndex {
+    entities: HashMap<UnityEntity, Entity>,
+    unity_entities: HashMap<Entity, UnityEntity>,
+}
+
+impl UnityEntityIndex {
+    pub fn get(&self, unity_entity: &UnityEntity) -> Option<Entity> {
+        self.entities.get(unity_entity).copied()
+    }
+}
+
+/// Adds spawned entities to the [`UnityEntityIndex`] and removes despawned ones.
+pub fn index_unity_entities(
+    mut index: ResMut<UnityEntityIndex>,
+    changed: Query<(Entity, &UnityEntity), Changed<UnityEntity>>,
+    mut removed: RemovedComponents<UnityEntity>,
+) {
+    for entity in removed.iter() {
+        let Some(unity_entity) = index.unity_entities.remove(&entity) else {
+            continue;
+        };
+        // unless another entity was spawned for it since
+        if index.entities.get(&unity_entity) == Some(&entity) {
+            index.entities.remove(&unity_entity);
+        }
+    }
+
+    for (entity, unity_entity) in changed.iter() {
+        if let Some(previous) = index.unity_entities.insert(entity, *unity_entity) {
+            index.entities.remove(&previous);
+        }
+        index.entities.insert(*unity_entity, entity);
+    }
+}
+
+/// Inserts a component that arrived from unity, marking it if it points at entities that
+/// still need to be resolved. Components breaking their validation rules are left out.
+pub fn insert_unity_component<T: UnityComponent + Component>(
+    entity: &mut EntityMut,
+    mut component: T,
+) {
+    if !is_valid(entity, &component) {
+        return;
+    }
+
+    if component
+        .entity_refs()
+        .iter()
+        .any(|(_, entity_ref)| entity_ref.is_pending())
+    {
+        entity.insert(PendingEntityRefs::<T>::default());
+    }
+
+    entity.insert(component);
+}
+
+/// Points the entity references of `T` at the bevy entities spawned for them. References to
+/// entities that are not spawned yet are retried every frame, up to [`MAX_PENDING_FRAMES`].
+pub fn resolve_entity_refs<T: UnityComponent + Component>(
+    mut commands: Commands,
+    mut pending: Query<(Entity, &mut T, &mut PendingEntityRefs<T>)>,
+    index: Res<UnityEntityIndex>,
+) {
+    for (entity, mut component, mut marker) in pending.iter_mut() {
+        let mut resolved = false;
+        let mut waiting = vec![];
+        for (field, entity_ref) in component.bypass_change_detection().entity_refs() {
+            if let UnityEntityRef::Pending(unity_entity) = entity_ref {
+                match index.get(unity_entity) {
+                    Some(target) => {
+                        *entity_ref = UnityEntityRef::Resolved(target);
+                        resolved = true;
+                    }
+                    None => waiting.push(field),
+                }
+            }
+        }
+
+        if resolved {
+            component.set_changed();
+        }
+
+        marker.frames += 1;
+        if waiting.is_empty() {
+            commands.entity(entity).remove::<PendingEntityRefs<T>>();
+        } else if marker.frames >= MAX_PENDING_FRAMES {
+            let name = std::any::type_name::<T>()
+                .rsplit("::")
+                .next()
+                .unwrap_or_default();
+            waiting.dedup();
+            for field in waiting {
+                warn!("`{name}.{field}` of {entity:?} points at an entity that did not spawn in {MAX_PENDING_FRAMES} frames, it may be disabled, stripped or in another subscene");
+            }
+            commands.entity(entity).remove::<PendingEntityRefs<T>>();
+        }
+    }
+}
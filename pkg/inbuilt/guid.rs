@@ -1,11 +1,49 @@
+use crate::FieldSchema;
 use genco::prelude::*;
-use unrust_proc_macro::unity_authoring;
+use std::fmt;
+use std::mem::offset_of;
+use std::str::FromStr;
 
-#[unity_authoring]
+#[derive(bevy::prelude::Component, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[repr(C)]
 pub struct UnityGUID {
     pub hash: [u32; 4],
 }
 
+/// Parses the `guid:` of a `.meta` file. Unity writes each of the four words with its lowest
+/// nibble first, so `ff2a6f8f..` is `hash[0] == 0xf8f6a2ff`.
+impl FromStr for UnityGUID {
+    type Err = anyhow::Error;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        let text = text.as_bytes();
+        if text.len() != 32 {
+            anyhow::bail!("a guid is 32 hex digits, found {}", text.len());
+        }
+
+        let mut hash = [0u32; 4];
+        for (index, digit) in text.iter().enumerate() {
+            let Some(nibble) = (*digit as char).to_digit(16) else {
+                anyhow::bail!("`{}` is not a hex digit", *digit as char);
+            };
+            hash[index / 8] |= nibble << (index % 8 * 4);
+        }
+
+        Ok(UnityGUID { hash })
+    }
+}
+
+impl fmt::Display for UnityGUID {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for word in self.hash {
+            for index in 0..8 {
+                write!(f, "{:x}", word >> (index * 4) & 0xf)?;
+            }
+        }
+        Ok(())
+    }
+}
+
 #[allow(non_snake_case)]
 pub fn UnityGUID_ingest_component(entity: &mut bevy::ecs::world::EntityMut, val: &UnityGUID) {
     entity.insert(*val);
@@ -42,3 +80,61 @@ pub fn UnityGUID_CSHARP_TOKEN() -> csharp::Tokens {
         }
     }
 }
+
+#[allow(non_snake_case)]
+pub fn UnityGUID_SCHEMA_FIELDS() -> Vec<FieldSchema> {
+    vec![FieldSchema::new::<[u32; 4]>(
+        "hash",
+        "hash",
+        "[u32; 4]",
+        "fixed uint[4]",
+        offset_of!(UnityGUID, hash),
+    )]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reverses_the_nibbles_of_each_word() {
+        let guid: UnityGUID = "ff2a6f8f24bef6774ae11c3b74f82424".parse().unwrap();
+        assert_eq!(guid.hash, [0xf8f6a2ff, 0x776feb42, 0xb3c11ea4, 0x42428f47]);
+
+        let guid: UnityGUID = "10000000000000000000000000000000".parse().unwrap();
+        assert_eq!(guid.hash, [1, 0, 0, 0]);
+        let guid: UnityGUID = "0000000000000000e000000000000000".parse().unwrap();
+        assert_eq!(guid.hash, [0, 0, 0xe, 0]);
+    }
+
+    #[test]
+    fn displays_as_unity_writes_it() {
+        for text in [
+            "ff2a6f8f24bef6774ae11c3b74f82424",
+            "0000000000000000e000000000000000",
+            "02b1acfdb8e25329ca3dcfb9f28a7fda",
+        ] {
+            assert_eq!(text.parse::<UnityGUID>().unwrap().to_string(), text);
+        }
+
+        let guid = UnityGUID {
+            hash: [0xf8f6a2ff, 0x776feb42, 0xb3c11ea4, 0x42428f47],
+        };
+        assert_eq!(guid.to_string(), "ff2a6f8f24bef6774ae11c3b74f82424");
+    }
+
+    #[test]
+    fn rejects_malformed_guids() {
+        assert!("ff2a6f8f".parse::<UnityGUID>().is_err());
+        assert!("ff2a6f8f24bef6774ae11c3b74f8242g"
+            .parse::<UnityGUID>()
+            .is_err());
+        assert_eq!(
+            "FF2A6F8F24BEF6774AE11C3B74F82424"
+                .parse::<UnityGUID>()
+                .unwrap()
+                .to_string(),
+            "ff2a6f8f24bef6774ae11c3b74f82424"
+        );
+    }
+}
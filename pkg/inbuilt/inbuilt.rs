@@ -1,21 +1,68 @@
+mod asset;
+mod component;
+mod config;
+mod curve;
 mod entity;
+mod entity_ref;
 mod guid;
 mod parent;
+mod resource;
+mod schema;
 mod transform;
+mod validate;
 use genco::fmt;
 use genco::prelude::*;
-use std::fs::File;
 
+pub use asset::*;
 use bevy::ecs::world::EntityMut;
+pub use component::*;
+pub use config::*;
+pub use curve::*;
 pub use entity::*;
+pub use entity_ref::*;
 pub use guid::*;
 pub use parent::*;
+pub use resource::*;
+pub use schema::*;
 pub use transform::*;
 use unrust_proc_macro::generate_inbuilt;
+pub use validate::*;
 
 generate_inbuilt!((UnityParent, UnityEntity, UnityGUID, UnityTransform));
 
 pub fn write_csharp_inbuilt(path: &str) -> anyhow::Result<()> {
+    let output = render_csharp_inbuilt()?;
+    if std::fs::read_to_string(path).is_ok_and(|existing| existing == output) {
+        return Ok(());
+    }
+
+    std::fs::write(path, output)?;
+    Ok(())
+}
+
+/// Fails if the file at `path` does not match what [`write_csharp_inbuilt`] would write.
+pub fn check_csharp_inbuilt(path: &str) -> anyhow::Result<()> {
+    let output = render_csharp_inbuilt()?;
+    let Ok(existing) = std::fs::read_to_string(path) else {
+        anyhow::bail!("inbuilt C# is out of date, rerun the codegen:\n+ {path}");
+    };
+
+    if existing != output {
+        let line = existing
+            .lines()
+            .zip(output.lines())
+            .take_while(|(a, b)| a == b)
+            .count()
+            + 1;
+        anyhow::bail!(
+            "inbuilt C# is out of date, rerun the codegen:\n~ {path} (differs from line {line})"
+        );
+    }
+
+    Ok(())
+}
+
+fn render_csharp_inbuilt() -> anyhow::Result<String> {
     let struct_layout = &csharp::import("System.Runtime.InteropServices", "StructLayout");
     let layout_kind = &csharp::import("System.Runtime.InteropServices", "LayoutKind");
     let unity_types = UNITY_TYPES;
@@ -60,9 +107,8 @@ pub fn write_csharp_inbuilt(path: &str) -> anyhow::Result<()> {
     let fmt = fmt::Config::from_lang::<Csharp>().with_indentation(fmt::Indentation::Space(4));
     let config = csharp::Config::default();
 
-    let file = File::create(path)?;
-    let mut w = fmt::IoWriter::new(file);
+    let mut w = fmt::FmtWriter::new(String::new());
     output.format_file(&mut w.as_formatter(&fmt), &config)?;
 
-    Ok(())
+    Ok(w.into_inner())
 }
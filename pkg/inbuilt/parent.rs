@@ -1,8 +1,10 @@
+use crate::FieldSchema;
 use bevy::prelude::*;
 use genco::prelude::*;
-use unrust_proc_macro::unity_authoring;
+use std::mem::offset_of;
 
-#[unity_authoring]
+#[derive(bevy::prelude::Component, Clone, Copy, Debug)]
+#[repr(C)]
 pub struct UnityParent {
     pub entity: u64,
 }
@@ -43,3 +45,14 @@ pub fn UnityParent_CSHARP_TOKEN() -> csharp::Tokens {
         }
     }
 }
+
+#[allow(non_snake_case)]
+pub fn UnityParent_SCHEMA_FIELDS() -> Vec<FieldSchema> {
+    vec![FieldSchema::new::<u64>(
+        "entity",
+        "parent",
+        "u64",
+        "ulong",
+        offset_of!(UnityParent, entity),
+    )]
+}
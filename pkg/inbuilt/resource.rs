@@ -0,0 +1,42 @@
+use bevy::ecs::world::EntityMut;
+use bevy::prelude::*;
+use bevy::utils::HashMap;
+use std::any::TypeId;
+
+use crate::validate::is_valid;
+use crate::{UnityComponent, UnityEntity};
+
+/// The entity each `#[unity_resource]` came from, to notice when a scene has several.
+#[derive(Resource, Default)]
+struct UnityResourceSources(HashMap<TypeId, Entity>);
+
+/// Inserts a `#[unity_resource]` that arrived on `entity` as a bevy resource. With more than
+/// one in the scene, the last one spawned wins.
+pub fn insert_unity_resource<T: UnityComponent + Resource>(entity: &mut EntityMut, resource: T) {
+    if !is_valid(entity, &resource) {
+        return;
+    }
+
+    let source = entity.id();
+    let unity_entity = entity.get::<UnityEntity>().copied();
+    entity.world_scope(|world| {
+        let mut sources = world.get_resource_or_insert_with(UnityResourceSources::default);
+        let previous = sources.0.insert(TypeId::of::<T>(), source);
+
+        // the entity it came from before may have been despawned with its scene
+        if previous.is_some_and(|previous| previous != source && world.get_entity(previous).is_some()) {
+            let name = std::any::type_name::<T>()
+                .rsplit("::")
+                .next()
+                .unwrap_or_default();
+            let unity_entity = unity_entity
+                .map(|e| format!("{}:{}", e.index, e.version))
+                .unwrap_or_default();
+            tracing::warn!(
+                "more than one entity has the {name} resource, using the one on unity entity {unity_entity}"
+            );
+        }
+
+        world.insert_resource(resource);
+    });
+}
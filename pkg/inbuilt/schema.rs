@@ -0,0 +1,45 @@
+use serde::Serialize;
+
+/// Describes the memory layout of a `repr(C)` type sent between unity and bevy.
+#[derive(Debug, Clone, Serialize)]
+pub struct TypeSchema {
+    pub name: String,
+    pub rust_path: String,
+    pub csharp_type: String,
+    /// The discriminant used for this type on the wire, eg: `InbuiltTypes` or `CustomType`.
+    /// Types only sent inside other types, like `#[unity_struct]`s, have none.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub wire_id: Option<u8>,
+    pub size: usize,
+    pub align: usize,
+    pub fields: Vec<FieldSchema>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct FieldSchema {
+    pub name: String,
+    pub csharp_name: String,
+    pub rust_type: String,
+    pub csharp_type: String,
+    pub offset: usize,
+    pub size: usize,
+}
+
+impl FieldSchema {
+    pub fn new<T>(
+        name: &str,
+        csharp_name: &str,
+        rust_type: &str,
+        csharp_type: &str,
+        offset: usize,
+    ) -> FieldSchema {
+        FieldSchema {
+            name: name.to_string(),
+            csharp_name: csharp_name.to_string(),
+            rust_type: rust_type.to_string(),
+            csharp_type: csharp_type.to_string(),
+            offset,
+            size: std::mem::size_of::<T>(),
+        }
+    }
+}
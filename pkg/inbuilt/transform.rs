@@ -1,8 +1,10 @@
+use crate::FieldSchema;
 use bevy::prelude::*;
 use genco::prelude::*;
-use unrust_proc_macro::unity_authoring;
+use std::mem::offset_of;
 
-#[unity_authoring]
+#[derive(bevy::prelude::Component, Clone, Copy, Debug)]
+#[repr(C)]
 pub struct UnityTransform {
     pub mat: [f32; 16],
 }
@@ -99,3 +101,14 @@ pub fn UnityTransform_CSHARP_TOKEN() -> csharp::Tokens {
         }
     }
 }
+
+#[allow(non_snake_case)]
+pub fn UnityTransform_SCHEMA_FIELDS() -> Vec<FieldSchema> {
+    vec![FieldSchema::new::<[f32; 16]>(
+        "mat",
+        "matrix",
+        "[f32; 16]",
+        "fixed float[16]",
+        offset_of!(UnityTransform, mat),
+    )]
+}
@@ -0,0 +1,68 @@
+use bevy::ecs::world::EntityMut;
+use std::fmt::Debug;
+
+use crate::{UnityComponent, UnityEntity};
+
+/// A field of a component from unity that breaks one of its `#[validate(..)]` rules.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct UnityValidationError {
+    pub field: &'static str,
+    pub message: String,
+}
+
+impl UnityValidationError {
+    pub fn new(field: &'static str, message: impl Into<String>) -> Self {
+        Self {
+            field,
+            message: message.into(),
+        }
+    }
+}
+
+pub fn validate_min<T: PartialOrd + Debug>(value: &T, min: T) -> Result<(), String> {
+    match *value < min {
+        true => Err(format!("is {value:?}, below the minimum of {min:?}")),
+        false => Ok(()),
+    }
+}
+
+pub fn validate_max<T: PartialOrd + Debug>(value: &T, max: T) -> Result<(), String> {
+    match *value > max {
+        true => Err(format!("is {value:?}, above the maximum of {max:?}")),
+        false => Ok(()),
+    }
+}
+
+pub fn validate_non_zero<T: PartialEq + Default>(value: &T) -> Result<(), String> {
+    match *value == T::default() {
+        true => Err("is zero".to_string()),
+        false => Ok(()),
+    }
+}
+
+/// Logs the fields of `value` breaking their rules, it is not added to bevy if there are any.
+pub(crate) fn is_valid<T: UnityComponent>(entity: &EntityMut, value: &T) -> bool {
+    let errors = value.validate();
+    if errors.is_empty() {
+        return true;
+    }
+
+    let name = std::any::type_name::<T>()
+        .rsplit("::")
+        .next()
+        .unwrap_or_default();
+    // spawn inserts the UnityEntity first
+    let unity_entity = entity
+        .get::<UnityEntity>()
+        .map(|e| format!("{}:{}", e.index, e.version))
+        .unwrap_or_default();
+    for error in errors {
+        tracing::error!(
+            "`{name}.{}` of unity entity {unity_entity} {}, not adding the {name}",
+            error.field,
+            error.message
+        );
+    }
+
+    false
+}
@@ -1,14 +1,462 @@
 use proc_macro::TokenStream;
 use quote::{format_ident, quote};
 use std::fmt::Write;
+use syn::ext::IdentExt;
+use syn::parse::{Parse, ParseStream};
+use syn::punctuated::Punctuated;
+use syn::Token;
 
 #[proc_macro_attribute]
-pub fn unity_authoring(_: TokenStream, item: TokenStream) -> TokenStream {
-    let input = proc_macro2::TokenStream::from(item);
+pub fn unity_authoring(attr: TokenStream, item: TokenStream) -> TokenStream {
+    authoring_struct(attr, item, Some(quote! { bevy::prelude::Component }))
+}
+
+/// Authored like a component, but inserted as a bevy `Resource`, eg: global tuning values.
+#[proc_macro_attribute]
+pub fn unity_resource(attr: TokenStream, item: TokenStream) -> TokenStream {
+    authoring_struct(attr, item, Some(quote! { bevy::prelude::Resource }))
+}
+
+/// Edited in unity as a ScriptableObject, which is exported to a file and loaded as a bevy
+/// `Asset`, eg: the settings of a level.
+#[proc_macro_attribute]
+pub fn unity_config(attr: TokenStream, item: TokenStream) -> TokenStream {
+    if let Some(arg) = proc_macro2::TokenStream::from(attr).into_iter().next() {
+        return syn::Error::new_spanned(arg, "#[unity_config] takes no arguments")
+            .to_compile_error()
+            .into();
+    }
+
+    let parsed = syn::parse_macro_input!(item as syn::ItemStruct);
+    // the file holds the wire struct, which can't point at a buffer or an entity
+    let unsupported = parsed.fields.iter().find(|field| match &field.ty {
+        syn::Type::Path(path) => path
+            .path
+            .segments
+            .last()
+            .is_some_and(|segment| segment.ident == "Vec" || segment.ident == "UnityEntityRef"),
+        _ => false,
+    });
+    if let Some(field) = unsupported {
+        return syn::Error::new_spanned(
+            &field.ty,
+            "#[unity_config] fields can not be a `Vec` or `UnityEntityRef`",
+        )
+        .to_compile_error()
+        .into();
+    }
+
+    let name = &parsed.ident;
+    let type_name = name.unraw().to_string();
+    let uuid = type_uuid(&type_name);
+    let extension = format!("{}.bytes", snake_case(&type_name));
+    let config = proc_macro2::TokenStream::from(authoring_struct(
+        TokenStream::new(),
+        quote!(#parsed).into(),
+        None,
+    ));
+
+    quote! {
+        #config
+
+        impl unrust::bevy::reflect::TypeUuid for #name {
+            const TYPE_UUID: unrust::bevy::utils::Uuid = unrust::bevy::utils::Uuid::from_u128(#uuid);
+        }
+
+        impl unrust::bevy::reflect::TypePath for #name {
+            fn type_path() -> &'static str {
+                concat!(module_path!(), "::", stringify!(#name))
+            }
+
+            fn short_type_path() -> &'static str {
+                stringify!(#name)
+            }
+        }
+
+        impl unrust::UnityConfig for #name {
+            const EXTENSION: &'static str = #extension;
+        }
+    }
+    .into()
+}
+
+/// A stable uuid for the asset type from its name, which the codegen keeps unique.
+fn type_uuid(name: &str) -> u128 {
+    // 128 bit FNV-1a
+    format!("unrust::unity_config::{name}")
+        .bytes()
+        .fold(0x6c62272e07bb014262b821756295c58d, |hash, byte| {
+            (hash ^ byte as u128).wrapping_mul(0x0000000001000000000000000000013b)
+        })
+}
+
+/// `LevelSettings` -> `level_settings`, the same as the codegen does for the exported file.
+fn snake_case(name: &str) -> String {
+    let mut snake = String::new();
+    let mut previous_lower = false;
+    for c in name.chars() {
+        if c.is_uppercase() && previous_lower {
+            snake.push('_');
+        }
+        previous_lower = c.is_lowercase() || c.is_ascii_digit();
+        snake.extend(c.to_lowercase());
+    }
+
+    snake
+}
+
+fn authoring_struct(
+    attr: TokenStream,
+    item: TokenStream,
+    derive: Option<proc_macro2::TokenStream>,
+) -> TokenStream {
+    let parsed = syn::parse_macro_input!(item as syn::ItemStruct);
+    let config = syn::parse_macro_input!(attr as AuthoringConfig);
+    let input = without_inspector_attributes(&parsed);
+    let name = &parsed.ident;
+    let (impl_generics, ty_generics, where_clause) = parsed.generics.split_for_impl();
+    let WireStruct {
+        definition,
+        wire_ty,
+        from_wire,
+        ..
+    } = wire_struct(&parsed);
+    let entity_refs = named_entity_refs(&parsed);
+    let derive = derive.map(|derive| quote! { #derive, });
+    let copy = (!owns_heap_data(&parsed)).then(|| quote! { Copy, });
+    let validate = validate_fields(&parsed).unwrap_or_else(syn::Error::into_compile_error);
+    let requires = &config.requires;
+    // the transform and menu are only read by the codegen
+    let requires = (!requires.is_empty()).then(|| {
+        quote! {
+            const _: fn() = || {
+                fn unity_component<T: unrust::UnityComponent>() {}
+                #(unity_component::<#requires>();)*
+            };
+        }
+    });
+
     quote! {
-        #[derive(bevy::prelude::Component, Clone, Copy, Debug)]
+        #[derive(#derive Clone, #copy Debug)]
         #[repr(C)]
         #input
+
+        #definition
+        #requires
+
+        impl #impl_generics unrust::UnityComponent for #name #ty_generics #where_clause {
+            type Wire = #wire_ty;
+
+            #[allow(unused_variables)]
+            fn from_wire(wire: &Self::Wire, assets: Option<&unrust::bevy::asset::AssetServer>) -> Option<Self> {
+                #from_wire
+            }
+
+            #[allow(unused_mut)]
+            fn entity_refs(&mut self) -> Vec<(&'static str, &mut unrust::UnityEntityRef)> {
+                let mut all = vec![];
+                #entity_refs
+                all
+            }
+
+            #[allow(unused_mut)]
+            fn validate(&self) -> Vec<unrust::UnityValidationError> {
+                let mut errors = vec![];
+                #validate
+                errors
+            }
+        }
+    }
+    .into()
+}
+
+/// Pushes the entity references of every field of `self` onto `all`, with the field's name.
+fn named_entity_refs(item: &syn::ItemStruct) -> proc_macro2::TokenStream {
+    let fields = item.fields.iter().filter_map(|field| {
+        let ident = field.ident.as_ref()?;
+        let field_ty = &field.ty;
+        Some(quote! {
+            let mut refs = vec![];
+            <#field_ty as unrust::UnityField>::entity_refs(&mut self.#ident, &mut refs);
+            all.extend(refs.into_iter().map(|entity_ref| (stringify!(#ident), entity_ref)));
+        })
+    });
+
+    quote! { #(#fields)* }
+}
+
+/// A plain struct that can be a field of a `#[unity_authoring]` struct, eg: a group of stats.
+#[proc_macro_attribute]
+pub fn unity_struct(_: TokenStream, item: TokenStream) -> TokenStream {
+    let parsed = syn::parse_macro_input!(item as syn::ItemStruct);
+    let input = without_inspector_attributes(&parsed);
+    let name = &parsed.ident;
+    let (impl_generics, ty_generics, where_clause) = parsed.generics.split_for_impl();
+    let WireStruct {
+        definition,
+        wire_ty,
+        from_wire,
+        entity_refs,
+    } = wire_struct(&parsed);
+
+    quote! {
+        #[derive(Clone, Copy, Debug)]
+        #[repr(C)]
+        #input
+
+        #definition
+
+        impl #impl_generics unrust::UnityField for #name #ty_generics #where_clause {
+            type Wire = #wire_ty;
+
+            #[allow(unused_variables)]
+            fn from_wire(wire: Self::Wire, assets: Option<&unrust::bevy::asset::AssetServer>) -> Option<Self> {
+                let wire = &wire;
+                #from_wire
+            }
+
+            #[allow(unused_variables)]
+            fn entity_refs<'a>(&'a mut self, refs: &mut Vec<&'a mut unrust::UnityEntityRef>) {
+                #entity_refs
+            }
+        }
+    }
+    .into()
+}
+
+/// The struct without the `header`, `tooltip`, `range`, `default` and `validate` field
+/// attributes, which are only read by the codegen and [`validate_fields`].
+fn without_inspector_attributes(item: &syn::ItemStruct) -> syn::ItemStruct {
+    let mut item = item.clone();
+    for field in item.fields.iter_mut() {
+        field.attrs.retain(|attr| {
+            !["header", "tooltip", "range", "default", "validate"]
+                .iter()
+                .any(|name| attr.path().is_ident(name))
+        });
+    }
+
+    item
+}
+
+/// Pushes an `UnityValidationError` onto `errors` for each `#[validate(..)]` rule a field of
+/// `self` breaks. `min`, `max` and `non_zero` check every element of arrays and Vecs.
+fn validate_fields(item: &syn::ItemStruct) -> syn::Result<proc_macro2::TokenStream> {
+    let mut checks = vec![];
+    for field in &item.fields {
+        let Some(ident) = &field.ident else {
+            continue;
+        };
+        let name = ident.unraw().to_string();
+        let element = match &field.ty {
+            syn::Type::Array(array) => Some(array.elem.as_ref()),
+            syn::Type::Path(path) => path.path.segments.last().and_then(|segment| {
+                match (&segment.arguments, segment.ident == "Vec") {
+                    (syn::PathArguments::AngleBracketed(args), true) => {
+                        args.args.iter().find_map(|arg| match arg {
+                            syn::GenericArgument::Type(ty) => Some(ty),
+                            _ => None,
+                        })
+                    }
+                    _ => None,
+                }
+            }),
+            _ => None,
+        };
+        let values = match element {
+            Some(_) => quote! { self.#ident.iter() },
+            None => quote! { std::iter::once(&self.#ident) },
+        };
+        let element = element.unwrap_or(&field.ty);
+
+        for attr in field
+            .attrs
+            .iter()
+            .filter(|attr| attr.path().is_ident("validate"))
+        {
+            attr.parse_nested_meta(|meta| {
+                let check = if meta.path.is_ident("min") || meta.path.is_ident("max") {
+                    if !is_number(element) {
+                        return Err(syn::Error::new_spanned(
+                            &field.ty,
+                            format!(
+                                "`min` and `max` only work on numbers, `{name}` is a `{}`",
+                                quote! { #element }.to_string().replace(' ', "")
+                            ),
+                        ));
+                    }
+                    let bound: syn::Expr = meta.value()?.parse()?;
+                    let check = match meta.path.is_ident("min") {
+                        true => quote! { unrust::validate_min },
+                        false => quote! { unrust::validate_max },
+                    };
+                    quote! { #check(value, #bound as #element) }
+                } else if meta.path.is_ident("non_zero") {
+                    quote! { unrust::validate_non_zero(value) }
+                } else if meta.path.is_ident("non_empty") {
+                    checks.push(quote! {
+                        if self.#ident.is_empty() {
+                            errors.push(unrust::UnityValidationError::new(#name, "is empty"));
+                        }
+                    });
+                    return Ok(());
+                } else if meta.path.is_ident("with") {
+                    let check: syn::Path = meta.value()?.parse()?;
+                    checks.push(quote! {
+                        if let Err(message) = #check(&self.#ident) {
+                            errors.push(unrust::UnityValidationError::new(#name, message));
+                        }
+                    });
+                    return Ok(());
+                } else {
+                    return Err(
+                        meta.error("expected `min`, `max`, `non_zero`, `non_empty` or `with`")
+                    );
+                };
+
+                // one error per rule, even if several elements break it
+                checks.push(quote! {
+                    if let Some(Err(message)) = #values.map(|value| #check).find(Result::is_err) {
+                        errors.push(unrust::UnityValidationError::new(#name, message));
+                    }
+                });
+                Ok(())
+            })?;
+        }
+    }
+
+    Ok(quote! { #(#checks)* })
+}
+
+/// The primitives `min` and `max` can cast their bound to.
+fn is_number(ty: &syn::Type) -> bool {
+    const NUMBERS: [&str; 10] = [
+        "u8", "i8", "u16", "i16", "u32", "i32", "u64", "i64", "f32", "f64",
+    ];
+    match ty {
+        syn::Type::Path(path) => path
+            .path
+            .get_ident()
+            .is_some_and(|ident| NUMBERS.iter().any(|number| ident == number)),
+        _ => false,
+    }
+}
+
+/// Components with a `Vec`, `String`, `Handle` or `AssetPath` can not be `Copy`, they are
+/// built from the wire struct instead.
+fn owns_heap_data(item: &syn::ItemStruct) -> bool {
+    item.fields.iter().any(|field| match &field.ty {
+        syn::Type::Path(path) => path.path.segments.last().is_some_and(|segment| {
+            ["Vec", "String", "Handle", "AssetPath"]
+                .iter()
+                .any(|name| segment.ident == name)
+        }),
+        _ => false,
+    })
+}
+
+/// The `#[repr(C)]` struct unity sends for a struct, where each field is what unity sends
+/// for it and gets validated when converting back.
+struct WireStruct {
+    definition: proc_macro2::TokenStream,
+    wire_ty: proc_macro2::TokenStream,
+    /// Evaluates to `Option<Self>` from a `wire: &#wire_ty`.
+    from_wire: proc_macro2::TokenStream,
+    /// Pushes the entity references of `self` onto `refs: &mut Vec<&mut UnityEntityRef>`.
+    entity_refs: proc_macro2::TokenStream,
+}
+
+fn wire_struct(item: &syn::ItemStruct) -> WireStruct {
+    let name = &item.ident;
+    let (impl_generics, ty_generics, where_clause) = item.generics.split_for_impl();
+
+    // nothing to validate without fields, codegen rejects tuple structs
+    let syn::Fields::Named(fields) = &item.fields else {
+        return WireStruct {
+            definition: quote! {},
+            wire_ty: quote! { Self },
+            from_wire: quote! { Some(*wire) },
+            entity_refs: quote! {},
+        };
+    };
+
+    let wire_name = format_ident!("{}UnrustWire", name);
+
+    let wire_fields = fields.named.iter().map(|field| {
+        let ident = &field.ident;
+        let field_ty = &field.ty;
+        quote! { pub #ident: <#field_ty as unrust::UnityField>::Wire }
+    });
+
+    let from_fields = fields.named.iter().map(|field| {
+        let ident = &field.ident;
+        let field_ty = &field.ty;
+        quote! { #ident: <#field_ty as unrust::UnityField>::from_wire(wire.#ident, assets)? }
+    });
+
+    let entity_refs = fields.named.iter().map(|field| {
+        let ident = &field.ident;
+        let field_ty = &field.ty;
+        quote! { <#field_ty as unrust::UnityField>::entity_refs(&mut self.#ident, refs); }
+    });
+
+    WireStruct {
+        definition: quote! {
+            #[doc(hidden)]
+            #[derive(Clone, Copy)]
+            #[repr(C)]
+            pub struct #wire_name #impl_generics #where_clause {
+                #(#wire_fields,)*
+            }
+        },
+        wire_ty: quote! { #wire_name #ty_generics },
+        from_wire: quote! {
+            Some(Self {
+                #(#from_fields,)*
+            })
+        },
+        entity_refs: quote! { #(#entity_refs)* },
+    }
+}
+
+/// A unit only enum that can be a field of a `#[unity_authoring]` struct. It is sent as its
+/// index, which is checked when the component arrives in rust.
+#[proc_macro_attribute]
+pub fn unity_enum(_: TokenStream, item: TokenStream) -> TokenStream {
+    let input = proc_macro2::TokenStream::from(item.clone());
+    let parsed = syn::parse_macro_input!(item as syn::ItemEnum);
+    let name = &parsed.ident;
+
+    if let Some(variant) = parsed
+        .variants
+        .iter()
+        .find(|v| !matches!(v.fields, syn::Fields::Unit))
+    {
+        return syn::Error::new_spanned(variant, "#[unity_enum] only supports unit variants")
+            .to_compile_error()
+            .into();
+    }
+
+    let variants = parsed.variants.iter().map(|variant| {
+        let ident = &variant.ident;
+        quote! { wire if wire == #name::#ident as u8 => Some(#name::#ident) }
+    });
+
+    quote! {
+        #[repr(u8)]
+        #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+        #input
+
+        impl unrust::UnityField for #name {
+            type Wire = u8;
+
+            fn from_wire(wire: Self::Wire, _: Option<&unrust::bevy::asset::AssetServer>) -> Option<Self> {
+                match wire {
+                    #(#variants,)*
+                    _ => None,
+                }
+            }
+        }
     }
     .into()
 }
@@ -93,6 +541,23 @@ pub fn generate_inbuilt(item: TokenStream) -> TokenStream {
         }
     });
 
+    let schema_types = enum_types.clone().enumerate().map(|(index, ident)| {
+        let index = index as u8;
+        let fields_fn = format_ident!("{}_SCHEMA_FIELDS", ident);
+
+        quote! {
+            TypeSchema {
+                name: stringify!(#ident).to_string(),
+                rust_path: concat!("unrust::", stringify!(#ident)).to_string(),
+                csharp_type: concat!("unrust.runtime.", stringify!(#ident)).to_string(),
+                wire_id: Some(#index),
+                size: std::mem::size_of::<#ident>(),
+                align: std::mem::align_of::<#ident>(),
+                fields: #fields_fn(),
+            }
+        }
+    });
+
     quote! {
         #[repr(u8)]
         pub enum InbuiltTypes {
@@ -132,6 +597,11 @@ pub fn generate_inbuilt(item: TokenStream) -> TokenStream {
         fn get_inbuilt_csharp_tokens() -> Vec<csharp::Tokens> {
             vec![#(#csharp_fns,)*]
         }
+
+        /// Layouts of the inbuilt types, in wire order.
+        pub fn inbuilt_schema() -> Vec<TypeSchema> {
+            vec![#(#schema_types,)*]
+        }
     }
     .into()
 }
@@ -181,14 +651,17 @@ pub fn unity_prefab(_: TokenStream, item: TokenStream) -> TokenStream {
 pub fn unrust_setup(attr: TokenStream, item: TokenStream) -> TokenStream {
     let input = proc_macro2::TokenStream::from(item.clone());
     let parsed = syn::parse_macro_input!(item as syn::ItemFn);
+    let config = syn::parse_macro_input!(attr as SetupConfig);
     let ident = parsed.sig.ident;
 
-    let custom_incoming = handle_custom_components(attr.clone());
-    let state_incoming = handle_custom_states(attr.clone());
+    let custom_incoming = handle_custom_components(&config.components, &config.resources);
+    let state_incoming = handle_custom_states(&config.states);
 
-    let states = custom_states(attr.clone());
-    let prefabs = prefab_resources(attr.clone());
-    let register = register_prefabs(attr.clone());
+    let states = custom_states(&config.states);
+    let entity_refs = resolve_entity_refs(&config.components);
+    let prefabs = prefab_resources(&config.prefabs);
+    let configs = config_assets(&config.configs);
+    let register = register_prefabs(&config.prefabs);
 
     quote! {
         #input
@@ -200,7 +673,9 @@ pub fn unrust_setup(attr: TokenStream, item: TokenStream) -> TokenStream {
         impl GamePlugin for Game {
             fn initialize(&self, app: &mut App) {
                 #states
+                #entity_refs
                 #prefabs
+                #configs
                 #ident(app);
             }
 
@@ -236,125 +711,260 @@ pub fn unrust_setup(attr: TokenStream, item: TokenStream) -> TokenStream {
     .into()
 }
 
-fn get_nth_tuple(item: TokenStream, n: usize) -> Option<syn::ExprTuple> {
-    let tokens = proc_macro2::TokenStream::from(item);
-    let config = syn::parse2::<syn::ExprTuple>(tokens).expect("expecting a tuple of tuples");
+/// The arguments of `#[unity_authoring(transform = "..", requires = [..], menu = "..")]`.
+/// Every argument is optional, but each may only be given once.
+#[derive(Default)]
+struct AuthoringConfig {
+    requires: Vec<syn::Path>,
+}
 
-    let Some(config) = config.elems.iter().nth(n) else {
-        panic!("expected at least n tuples")
-    };
+impl Parse for AuthoringConfig {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let mut config = AuthoringConfig::default();
+        let mut seen: Vec<String> = vec![];
+
+        while !input.is_empty() {
+            let key: syn::Ident = input.parse()?;
+            let name = key.to_string();
+            if seen.contains(&name) {
+                return Err(syn::Error::new(
+                    key.span(),
+                    format!("`{name}` is specified more than once"),
+                ));
+            }
+            input.parse::<Token![=]>()?;
+
+            match name.as_str() {
+                "transform" => {
+                    let usage: syn::LitStr = input.parse()?;
+                    let usages = [
+                        "none",
+                        "renderable",
+                        "dynamic",
+                        "world_space",
+                        "non_uniform_scale",
+                        "manual_override",
+                    ];
+                    if !usages.contains(&usage.value().as_str()) {
+                        return Err(syn::Error::new(
+                            usage.span(),
+                            format!("expected one of {}", usages.map(|usage| format!("\"{usage}\"")).join(", ")),
+                        ));
+                    }
+                }
+                "requires" => {
+                    let content;
+                    syn::bracketed!(content in input);
+                    config.requires = Punctuated::<syn::Path, Token![,]>::parse_terminated(&content)?
+                        .into_iter()
+                        .collect();
+                }
+                "menu" => {
+                    input.parse::<syn::LitStr>()?;
+                }
+                _ => {
+                    return Err(syn::Error::new(
+                        key.span(),
+                        format!(
+                            "unknown argument `{name}`, expected one of `transform`, `requires` or `menu`"
+                        ),
+                    ))
+                }
+            }
+            seen.push(name);
 
-    let syn::Expr::Tuple(types) = config else {
-        panic!("expected tuple for custom incoming types!");
-    };
+            if !input.is_empty() {
+                input.parse::<Token![,]>()?;
+            }
+        }
 
-    Some(types.clone())
+        Ok(config)
+    }
 }
 
-fn handle_custom_components(item: TokenStream) -> proc_macro2::TokenStream {
-    let Some(types) = get_nth_tuple(item, 0) else {
-        return quote! {
-            fn handle_custom_components(entity: &mut unrust::bevy::ecs::world::EntityMut, custom: *const u8, len: usize) {}
-        };
-    };
+/// The arguments of `#[unrust_setup(components = [..], resources = [..], configs = [..], states = [..], prefabs = [..])]`.
+/// Every section is optional, but each may only be given once.
+#[derive(Default)]
+struct SetupConfig {
+    components: Vec<syn::Path>,
+    resources: Vec<syn::Path>,
+    configs: Vec<syn::Path>,
+    states: Vec<syn::Path>,
+    prefabs: Vec<syn::Path>,
+}
 
-    let filtered = types.elems.iter().filter_map(|expr| {
-        let syn::Expr::Path(expr) = expr else {
-            return None;
-        };
+impl Parse for SetupConfig {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        if input.peek(syn::token::Paren) {
+            return Err(input.error(
+                "unrust_setup expects named sections, eg: #[unrust_setup(components = [..], resources = [..], configs = [..], states = [..], prefabs = [..])]",
+            ));
+        }
 
-        let last = expr.path.segments.last()?;
-        Some((last, &expr.path))
-    });
+        let mut config = SetupConfig::default();
+        let mut seen: Vec<String> = vec![];
+
+        let sections = Punctuated::<SetupSection, Token![,]>::parse_terminated(input)?;
+        for section in sections {
+            let key = section.key.to_string();
+            if seen.contains(&key) {
+                return Err(syn::Error::new(
+                    section.key.span(),
+                    format!("`{key}` is specified more than once"),
+                ));
+            }
+
+            let target = match key.as_str() {
+                "components" => &mut config.components,
+                "resources" => &mut config.resources,
+                "configs" => &mut config.configs,
+                "states" => &mut config.states,
+                "prefabs" => &mut config.prefabs,
+                _ => {
+                    return Err(syn::Error::new(
+                        section.key.span(),
+                        format!(
+                            "unknown unrust_setup section `{key}`, expected one of `components`, `resources`, `configs`, `states` or `prefabs`"
+                        ),
+                    ))
+                }
+            };
+
+            *target = section.types.into_iter().collect();
+            seen.push(key);
+        }
 
-    let custom_types = filtered.clone().enumerate().map(|(index, (exp, _))| {
+        Ok(config)
+    }
+}
+
+/// A single `key = [Type, Type, ..]` section of the setup arguments.
+struct SetupSection {
+    key: syn::Ident,
+    types: Punctuated<syn::Path, Token![,]>,
+}
+
+impl Parse for SetupSection {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let key: syn::Ident = input.parse()?;
+        input.parse::<Token![=]>()?;
+
+        let content;
+        syn::bracketed!(content in input);
+        let types = Punctuated::<syn::Path, Token![,]>::parse_terminated(&content)?;
+
+        Ok(SetupSection { key, types })
+    }
+}
+
+fn last_ident(path: &syn::Path) -> &syn::Ident {
+    // syn only parses paths with at least one segment
+    &path.segments.last().unwrap().ident
+}
+
+/// Dispatches the custom data unity sends to `components`, then `resources`, matching the
+/// order the codegen numbers them in.
+fn handle_custom_components(
+    components: &[syn::Path],
+    resources: &[syn::Path],
+) -> proc_macro2::TokenStream {
+    let types: Vec<_> = components.iter().chain(resources).collect();
+    if types.is_empty() {
+        return quote! {
+            unsafe fn handle_custom_components(_entity: &mut unrust::bevy::ecs::world::EntityMut, _custom: *const u8, _len: usize) {}
+        };
+    }
+
+    let custom_types = types.iter().enumerate().map(|(index, path)| {
+        let ident = last_ident(path);
         let index = index as u8;
         quote! {
-            #exp = #index
+            #ident = #index
         }
     });
 
-    let component_types = filtered.clone().map(|(exp, rest)| {
+    let component_types = types.iter().map(|path| {
+        let ident = last_ident(path);
         quote! {
-            pub #exp: #rest
+            pub #ident: <#path as unrust::UnityComponent>::Wire
         }
     });
 
-    let match_types = filtered.clone().map(|(exp, _)| {
+    let match_types = types.iter().enumerate().map(|(index, path)| {
+        let ident = last_ident(path);
+        let insert = match index < components.len() {
+            true => quote! { unrust::insert_unity_component },
+            false => quote! { unrust::insert_unity_resource },
+        };
         quote! {
-            CustomTypes::#exp => entity.insert(ele.value.#exp)
+            CustomTypes::#ident => match <#path as unrust::UnityComponent>::from_wire(&ele.value.#ident, assets.as_ref()) {
+                Some(component) => #insert(entity, component),
+                None => unrust::tracing::warn!("ignoring {} with invalid field values from unity", stringify!(#ident)),
+            }
         }
     });
 
-    if filtered.count() > 0 {
-        quote! {
-            #[repr(u8)]
-            pub enum CustomTypes {
-                #(#custom_types,)*
-            }
+    quote! {
+        #[repr(u8)]
+        pub enum CustomTypes {
+            #(#custom_types,)*
+        }
 
-            #[allow(non_snake_case)]
-            union CustomComponents {
-                #(#component_types,)*
-            }
+        #[allow(non_snake_case)]
+        #[repr(C)]
+        union CustomComponents {
+            #(#component_types,)*
+        }
 
-            #[repr(C)]
-            struct CustomData {
-                pub ty: CustomTypes,
-                pub value: CustomComponents,
-            }
+        #[repr(C)]
+        struct CustomData {
+            pub ty: CustomTypes,
+            pub value: CustomComponents,
+        }
 
-            unsafe fn handle_custom_components(entity: &mut unrust::bevy::ecs::world::EntityMut, custom: *const u8, len: usize) {
-                let components = unsafe { std::slice::from_raw_parts(custom as *const CustomData, len) };
-                for ele in components {
-                    match ele.ty {
-                        #(#match_types,)*
-                    };
+        unsafe fn handle_custom_components(entity: &mut unrust::bevy::ecs::world::EntityMut, custom: *const u8, len: usize) {
+            // unity's array is only 8 byte aligned, while a component with glam fields makes
+            // CustomData 16 byte aligned, so every element is copied out before it is read
+            let custom = custom as *const CustomData;
+            // handles are loaded with the world's server, it is cloned as inserting borrows the entity
+            let assets = entity.world().get_resource::<unrust::bevy::asset::AssetServer>().cloned();
+            for index in 0..len {
+                let ele = unsafe { custom.add(index).read_unaligned() };
+                match ele.ty {
+                    #(#match_types,)*
                 };
-            }
-        }
-    } else {
-        quote! {
-            fn handle_custom_components(entity: &mut unrust::bevy::ecs::world::EntityMut, custom: *const u8, len: usize) {}
+            };
         }
     }
 }
 
-fn handle_custom_states(item: TokenStream) -> proc_macro2::TokenStream {
-    let Some(types) = get_nth_tuple(item, 1) else {
+fn handle_custom_states(types: &[syn::Path]) -> proc_macro2::TokenStream {
+    if types.is_empty() {
         return quote! {
-            fn handle_custom_components(entity: &mut unrust::bevy::ecs::world::EntityMut, custom: *const u8, len: usize) {}
+            unsafe fn handle_custom_states(_entity: &mut unrust::bevy::ecs::world::EntityMut, _custom: *const u8, _len: usize) {}
         };
-    };
-
-    let filtered = types.elems.iter().filter_map(|expr| {
-        let syn::Expr::Path(expr) = expr else {
-            return None;
-        };
-
-        let last = expr.path.segments.last()?;
-        Some((last, &expr.path))
-    });
+    }
 
-    let custom_types = filtered.clone().enumerate().map(|(index, (exp, _))| {
+    let custom_types = types.iter().enumerate().map(|(index, path)| {
+        let ident = last_ident(path);
         let index = index as u8;
         quote! {
-            #exp = #index
+            #ident = #index
         }
     });
 
-    let match_types = filtered.clone().map(|(exp, _)| {
-        let ident = &exp.ident;
+    let match_types = types.iter().map(|path| {
+        let ident = last_ident(path);
         let comp_name = format_ident!("Custom{ident}");
         quote! {
-            CustomStates::#exp => {
+            CustomStates::#ident => {
                 entity.insert(#comp_name { val: ele.value });
             }
         }
     });
 
-    let custom_components = filtered.clone().map(|(exp, p)| {
-        let ident = &exp.ident;
+    let custom_components = types.iter().map(|path| {
+        let ident = last_ident(path);
         let comp_name = format_ident!("Custom{ident}");
         let fn_name = format_ident!("update_{ident}");
         quote! {
@@ -365,7 +975,7 @@ fn handle_custom_states(item: TokenStream) -> proc_macro2::TokenStream {
 
             #[allow(non_snake_case)]
             fn #fn_name(
-                mut next_state: ResMut<NextState<#p>>,
+                mut next_state: ResMut<NextState<#path>>,
                 entities: Query<&#comp_name, Changed<#comp_name>>,
             ) {
                 let Ok(state) = entities.get_single() else {
@@ -381,56 +991,67 @@ fn handle_custom_states(item: TokenStream) -> proc_macro2::TokenStream {
         }
     });
 
-    if filtered.count() > 0 {
-        quote! {
-            #(#custom_components)*
+    quote! {
+        #(#custom_components)*
 
-            #[repr(u8)]
-            #[derive(Clone, Debug, PartialEq)]
-            pub enum CustomStates {
-                #(#custom_types,)*
-            }
+        #[repr(u8)]
+        #[derive(Clone, Debug, PartialEq)]
+        pub enum CustomStates {
+            #(#custom_types,)*
+        }
 
-            #[repr(C)]
-            struct CustomStateData {
-                pub ty: CustomStates,
-                pub value: u8,
-            }
+        #[repr(C)]
+        struct CustomStateData {
+            pub ty: CustomStates,
+            pub value: u8,
+        }
 
-            unsafe fn handle_custom_states(entity: &mut unrust::bevy::ecs::world::EntityMut, custom: *const u8, len: usize) {
-                let components = unsafe { std::slice::from_raw_parts(custom as *const CustomStateData, len) };
-                for ele in components {
-                    match ele.ty {
-                        #(#match_types,)*
-                    };
+        unsafe fn handle_custom_states(entity: &mut unrust::bevy::ecs::world::EntityMut, custom: *const u8, len: usize) {
+            let custom = custom as *const CustomStateData;
+            for index in 0..len {
+                let ele = unsafe { custom.add(index).read_unaligned() };
+                match ele.ty {
+                    #(#match_types,)*
                 };
-            }
-        }
-    } else {
-        quote! {
-            unsafe fn handle_custom_states(entity: &mut unrust::bevy::ecs::world::EntityMut, custom: *const u8, len: usize) {}
+            };
         }
     }
 }
 
-fn custom_states(item: TokenStream) -> proc_macro2::TokenStream {
-    let Some(states) = get_nth_tuple(item, 1) else {
+/// Components pointing at entities that had not spawned yet get resolved before the game's systems run.
+fn resolve_entity_refs(components: &[syn::Path]) -> proc_macro2::TokenStream {
+    if components.is_empty() {
         return quote! {};
-    };
+    }
 
-    let filtered = states.elems.iter().filter_map(|expr| {
-        let syn::Expr::Path(expr) = expr else {
-            return None;
-        };
+    let systems = components.iter().map(|path| {
+        quote! {
+            app.add_systems(
+                PreUpdate,
+                unrust::bevy::prelude::IntoSystemConfigs::after(
+                    unrust::resolve_entity_refs::<#path>,
+                    unrust::index_unity_entities,
+                ),
+            );
+        }
+    });
 
-        let last = expr.path.segments.last()?;
-        let comp_name = &last.ident;
-        let fn_name = format_ident!("update_{comp_name}");
+    quote! {
+        app.init_resource::<unrust::UnityEntityIndex>();
+        app.add_systems(PreUpdate, unrust::index_unity_entities);
+        #(#systems)*
+    }
+}
 
-        Some(quote! {
-            app.add_state::<#expr>();
+fn custom_states(states: &[syn::Path]) -> proc_macro2::TokenStream {
+    let filtered = states.iter().map(|path| {
+        let ident = last_ident(path);
+        let fn_name = format_ident!("update_{ident}");
+
+        quote! {
+            app.add_state::<#path>();
             app.add_systems(PostUpdate, #fn_name);
-        })
+        }
     });
 
     quote! {
@@ -438,23 +1059,36 @@ fn custom_states(item: TokenStream) -> proc_macro2::TokenStream {
     }
 }
 
-fn prefab_resources(item: TokenStream) -> proc_macro2::TokenStream {
-    let Some(states) = get_nth_tuple(item, 2) else {
-        return quote! {};
-    };
+/// Registers the asset and loader of every `#[unity_config]`.
+fn config_assets(configs: &[syn::Path]) -> proc_macro2::TokenStream {
+    let plugins = configs.iter().map(|path| {
+        quote! {
+            app.add_plugins(unrust::UnityConfigPlugin::<#path>::default());
+        }
+    });
 
-    let filtered = states.elems.iter().filter_map(|expr| {
-        let syn::Expr::Path(expr) = expr else {
-            return None;
-        };
+    quote! {
+        #(#plugins)*
+    }
+}
 
-        let last = expr.path.segments.last()?;
-        let comp_name = &last.ident;
-        let res_name = format_ident!("{comp_name}Resource");
+/// `path::to::CubePrefabs` -> `path::to::CubePrefabsResource`, the resource generated by `unity_prefab`.
+fn prefab_resource_path(path: &syn::Path) -> syn::Path {
+    let mut res_path = path.clone();
+    if let Some(last) = res_path.segments.last_mut() {
+        last.ident = format_ident!("{}Resource", last.ident);
+    }
 
-        Some(quote! {
+    res_path
+}
+
+fn prefab_resources(prefabs: &[syn::Path]) -> proc_macro2::TokenStream {
+    let filtered = prefabs.iter().map(|path| {
+        let res_name = prefab_resource_path(path);
+
+        quote! {
             app.insert_resource(#res_name::default());
-        })
+        }
     });
 
     quote! {
@@ -462,38 +1096,21 @@ fn prefab_resources(item: TokenStream) -> proc_macro2::TokenStream {
     }
 }
 
-fn register_prefabs(item: TokenStream) -> proc_macro2::TokenStream {
-    let Some(states) = get_nth_tuple(item, 2) else {
-        return quote! {};
-    };
-
-    let filtered = states
-        .elems
-        .iter()
-        .filter_map(|expr| {
-            let syn::Expr::Path(expr) = expr else {
-                return None;
-            };
+fn register_prefabs(prefabs: &[syn::Path]) -> proc_macro2::TokenStream {
+    let filtered = prefabs.iter().enumerate().map(|(index, path)| {
+        let count = index as i32;
+        let res_name = prefab_resource_path(path);
 
-            Some(expr)
-        })
-        .enumerate()
-        .map(|(index, ident)| {
-            let count = index as i32;
-            let ident = quote! { #ident };
-            let ident: syn::Expr =
-                syn::parse_str(&format!("{}Resource", ident.to_string().replace(' ', ""))).unwrap();
-
-            Some(quote! {
-                #count => {
-                    let Some(mut res) = world.get_resource_mut::<#ident>() else {
-                        return;
-                    };
+        quote! {
+            #count => {
+                let Some(mut res) = world.get_resource_mut::<#res_name>() else {
+                    return;
+                };
 
-                    res.insert_prefabs(guids);
-                }
-            })
-        });
+                res.insert_prefabs(guids);
+            }
+        }
+    });
 
     quote! {
         let guids = unsafe { std::slice::from_raw_parts(prefabs.guids, prefabs.len) };
@@ -503,3 +1120,56 @@ fn register_prefabs(item: TokenStream) -> proc_macro2::TokenStream {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn validate(item: syn::ItemStruct) -> Result<String, String> {
+        validate_fields(&item)
+            .map(|checks| checks.to_string())
+            .map_err(|err| err.to_string())
+    }
+
+    #[test]
+    fn bounds_numbers_and_their_collections() {
+        let checks = validate(syn::parse_quote! {
+            struct Stats {
+                #[validate(min = 0, max = 100)]
+                health: u32,
+                #[validate(min = 0.5)]
+                speeds: Vec<f32>,
+                #[validate(max = 3)]
+                lanes: [i8; 4],
+            }
+        })
+        .unwrap();
+
+        assert!(checks.contains("0 as u32"));
+        assert!(checks.contains("0.5 as f32"));
+        assert!(checks.contains("3 as i8"));
+    }
+
+    #[test]
+    fn rejects_bounds_on_other_types() {
+        let items: [syn::ItemStruct; 4] = [
+            syn::parse_quote! { struct Maths { #[validate(min = 0)] offset: Vec3 } },
+            syn::parse_quote! { struct Maths { #[validate(max = 1)] offsets: Vec<Vec3> } },
+            syn::parse_quote! { struct Flags { #[validate(min = 0)] flag: bool } },
+            syn::parse_quote! { struct Text { #[validate(max = 3)] name: String } },
+        ];
+        for item in items {
+            let err = validate(item).unwrap_err();
+            assert!(
+                err.starts_with("`min` and `max` only work on numbers"),
+                "{err}"
+            );
+        }
+
+        let err = validate(syn::parse_quote! {
+            struct Maths { #[validate(min = 0)] offset: Vec3 }
+        })
+        .unwrap_err();
+        assert!(err.ends_with("`offset` is a `Vec3`"), "{err}");
+    }
+}
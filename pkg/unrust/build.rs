@@ -1,8 +1,20 @@
 fn main() -> anyhow::Result<()> {
+    const PATH: &str = "../../unity/sdk/Runtime/InbuiltGenerated.cs";
+
+    println!("cargo:rerun-if-changed=build.rs");
+    println!("cargo:rerun-if-env-changed=UNRUST_DEV");
+    println!("cargo:rerun-if-env-changed=UNRUST_CHECK");
+
+    // the sdk is only next to the crate in this repo, not when a game gets unrust from crates.io.
+    // a game's own build script checks its generated code
     if std::env::var_os("UNRUST_DEV").is_none() {
         return Ok(());
     }
 
-    inbuilt::write_csharp_inbuilt("../../unity/sdk/Runtime/InbuiltGenerated.cs")?;
+    if std::env::var_os("UNRUST_CHECK").is_some() {
+        return inbuilt::check_csharp_inbuilt(PATH);
+    }
+
+    inbuilt::write_csharp_inbuilt(PATH)?;
     Ok(())
 }
@@ -0,0 +1,241 @@
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use bevy::{
+    asset::{AssetLoader, HandleId, LoadContext, LoadState, LoadedAsset},
+    prelude::*,
+    reflect::{TypePath, TypeUuid},
+    utils::{BoxedFuture, HashMap, HashSet},
+};
+use inbuilt::UnityGUID;
+
+use crate::yaml::UnityFileRef;
+
+/// The `guid:` of a `.meta` file, loaded for every asset to build the [`UnityAssetIndex`].
+#[derive(Debug, Clone, TypeUuid, TypePath)]
+#[uuid = "3b8f2d6a-9e41-4c7d-b0a5-62e1f8c94d17"]
+pub struct UnityMeta {
+    pub guid: UnityGUID,
+}
+
+/// Loads `.meta` files as a [`UnityMeta`].
+#[derive(Default)]
+pub struct UnityMetaLoader;
+
+impl AssetLoader for UnityMetaLoader {
+    fn load<'a>(
+        &'a self,
+        bytes: &'a [u8],
+        load_context: &'a mut LoadContext,
+    ) -> BoxedFuture<'a, Result<(), bevy::asset::Error>> {
+        Box::pin(async move {
+            let text = std::str::from_utf8(bytes)?;
+            let meta = parse_meta(text)
+                .with_context(|| format!("can not read {}", load_context.path().display()))?;
+            load_context.set_default_asset(LoadedAsset::new(meta));
+            Ok(())
+        })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["meta"]
+    }
+}
+
+fn parse_meta(text: &str) -> Result<UnityMeta> {
+    let Some(guid) = text.lines().find_map(|line| line.strip_prefix("guid:")) else {
+        anyhow::bail!("no `guid:` line");
+    };
+    Ok(UnityMeta {
+        guid: guid.trim().parse()?,
+    })
+}
+
+/// Every asset under the base path by the guid unity gave it in its `.meta` file, eg: to load
+/// the prefab a [`UnityGUID`] or a [`UnityFileRef`] points at. The `.meta` files are scanned at
+/// startup and load in the background, so lookups can miss until [`UnityAssetIndex::is_ready`].
+/// They are reloaded when unity changes them, but bevy only watches files it has loaded, so
+/// files created after startup are not indexed until the game restarts.
+#[derive(Resource, Default)]
+pub struct UnityAssetIndex {
+    paths: HashMap<UnityGUID, PathBuf>,
+    guids: HashMap<PathBuf, UnityGUID>,
+    /// Keeps the `.meta` files loaded so they are watched for changes.
+    metas: Vec<Handle<UnityMeta>>,
+    /// The `.meta` files found at startup that have neither been indexed nor failed to load.
+    pending: HashSet<HandleId>,
+    scanned: bool,
+}
+
+impl UnityAssetIndex {
+    /// The path of the asset, relative to the base path.
+    pub fn path(&self, guid: &UnityGUID) -> Option<&Path> {
+        self.paths.get(guid).map(PathBuf::as_path)
+    }
+
+    pub fn guid(&self, path: impl AsRef<Path>) -> Option<UnityGUID> {
+        self.guids.get(path.as_ref()).copied()
+    }
+
+    /// The path of the asset holding the object, [`None`] when it is in the same file.
+    pub fn resolve(&self, reference: &UnityFileRef) -> Option<&Path> {
+        let guid = reference.guid.as_deref()?.parse().ok()?;
+        self.path(&guid)
+    }
+
+    /// Starts loading the asset, [`None`] when no asset has the guid.
+    pub fn load<T: bevy::asset::Asset>(
+        &self,
+        server: &AssetServer,
+        guid: &UnityGUID,
+    ) -> Option<Handle<T>> {
+        Some(server.load(self.path(guid)?))
+    }
+
+    /// Whether every `.meta` file found at startup has been indexed or failed to load.
+    pub fn is_ready(&self) -> bool {
+        self.scanned && self.pending.is_empty()
+    }
+
+    pub fn len(&self) -> usize {
+        self.paths.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.paths.is_empty()
+    }
+
+    fn insert(&mut self, path: PathBuf, guid: UnityGUID) {
+        self.remove(&path);
+        if let Some(old) = self.paths.insert(guid, path.clone()) {
+            self.guids.remove(&old);
+        }
+        self.guids.insert(path, guid);
+    }
+
+    fn remove(&mut self, path: &Path) {
+        if let Some(guid) = self.guids.remove(path) {
+            self.paths.remove(&guid);
+        }
+    }
+}
+
+pub(crate) fn scan_meta_files(server: Res<AssetServer>, mut index: ResMut<UnityAssetIndex>) {
+    let mut folders = vec![PathBuf::new()];
+    while let Some(folder) = folders.pop() {
+        let children = match server.asset_io().read_directory(&folder) {
+            Ok(children) => children,
+            Err(err) => {
+                warn!("can not index {}: {err}", folder.display());
+                continue;
+            }
+        };
+
+        for child in children {
+            if server.asset_io().is_dir(&child) {
+                folders.push(child);
+            } else if child.extension().is_some_and(|ext| ext == "meta") {
+                let meta: Handle<UnityMeta> = server.load(child);
+                index.pending.insert(meta.id());
+                index.metas.push(meta);
+            }
+        }
+    }
+    index.scanned = true;
+}
+
+pub(crate) fn update_asset_index(
+    server: Res<AssetServer>,
+    metas: Res<Assets<UnityMeta>>,
+    mut events: EventReader<AssetEvent<UnityMeta>>,
+    mut index: ResMut<UnityAssetIndex>,
+) {
+    for event in events.iter() {
+        let (AssetEvent::Created { handle }
+        | AssetEvent::Modified { handle }
+        | AssetEvent::Removed { handle }) = event;
+        let Some(path) = server.get_handle_path(handle) else {
+            continue;
+        };
+        // the asset is the meta path without `.meta`, eg: `Prefabs/Cube.prefab`
+        let path = path.path().with_extension("");
+
+        match metas.get(handle) {
+            Some(meta) => index.insert(path, meta.guid),
+            None => index.remove(&path),
+        }
+        index.pending.remove(&handle.id());
+    }
+
+    // a `.meta` file that can not be read never sends an event
+    if !index.pending.is_empty() {
+        index
+            .pending
+            .retain(|meta| server.get_load_state(*meta) != LoadState::Failed);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_the_guid_of_a_meta_file() {
+        let text = include_str!("../../unity/example/Assets/Prefabs/Cube.prefab.meta");
+        let meta = parse_meta(text).unwrap();
+        assert_eq!(meta.guid.to_string(), "ff2a6f8f24bef6774ae11c3b74f82424");
+        assert_eq!(
+            meta.guid.hash,
+            [0xf8f6a2ff, 0x776feb42, 0xb3c11ea4, 0x42428f47]
+        );
+    }
+
+    #[test]
+    fn is_ready_once_every_meta_file_loaded_or_failed() {
+        let dir = tempfile::tempdir().unwrap();
+        let dir = dir.path();
+        std::fs::create_dir_all(dir.join("Prefabs")).unwrap();
+        std::fs::write(
+            dir.join("Prefabs/Cube.prefab.meta"),
+            include_str!("../../unity/example/Assets/Prefabs/Cube.prefab.meta"),
+        )
+        .unwrap();
+        std::fs::write(dir.join("Broken.asset.meta"), "fileFormatVersion: 2\n").unwrap();
+
+        let mut app = App::new();
+        app.add_plugins((
+            TaskPoolPlugin::default(),
+            AssetPlugin {
+                asset_folder: dir.to_string_lossy().into_owned(),
+                watch_for_changes: None,
+            },
+        ))
+        .add_asset::<UnityMeta>()
+        .init_asset_loader::<UnityMetaLoader>()
+        .init_resource::<UnityAssetIndex>()
+        .add_systems(Startup, scan_meta_files)
+        .add_systems(PreUpdate, update_asset_index);
+        assert!(!app.world.resource::<UnityAssetIndex>().is_ready());
+
+        for _ in 0..1000 {
+            app.update();
+            if app.world.resource::<UnityAssetIndex>().is_ready() {
+                break;
+            }
+            std::thread::sleep(std::time::Duration::from_millis(1));
+        }
+
+        let index = app.world.resource::<UnityAssetIndex>();
+        assert!(index.is_ready());
+        assert_eq!(index.len(), 1);
+        let guid = "ff2a6f8f24bef6774ae11c3b74f82424".parse().unwrap();
+        assert_eq!(index.path(&guid), Some(Path::new("Prefabs/Cube.prefab")));
+        assert_eq!(index.guid("Prefabs/Cube.prefab"), Some(guid));
+    }
+
+    #[test]
+    fn rejects_meta_files_without_a_guid() {
+        let err = parse_meta("fileFormatVersion: 2\nfolderAsset: yes\n").unwrap_err();
+        assert_eq!(err.to_string(), "no `guid:` line");
+    }
+}
@@ -0,0 +1,72 @@
+use std::mem::{align_of, size_of};
+
+use unrust::bevy;
+use unrust::bevy::prelude::*;
+use unrust::{unity_authoring, unity_resource, unrust_setup, CHeaderBackend, GamePlugin};
+
+mod common;
+
+#[unity_authoring]
+pub struct Spin {
+    pub rotation: Quat,
+    pub tint: Vec4,
+    pub speed: f32,
+}
+
+#[unity_authoring]
+pub struct Marker;
+
+#[unity_resource]
+pub struct Difficulty {
+    pub level: u8,
+}
+
+#[unrust_setup(components = [Spin, Marker], resources = [Difficulty])]
+pub fn setup(_app: &mut App) {}
+
+fn header() -> String {
+    let (_, files) = common::generate_with("c_header.rs", CHeaderBackend).unwrap();
+    files.get("unrust.h").unwrap().to_string()
+}
+
+#[test]
+fn glam_fields_are_aligned_like_rust() {
+    assert_eq!(align_of::<SpinUnrustWire>(), 16);
+    assert_eq!(size_of::<SpinUnrustWire>(), 48);
+
+    let header = header();
+    let spin = format!(
+        "typedef struct Spin {{\n    _Alignas({}) float rotation[4];\n    _Alignas({}) float tint[4];\n    float speed;\n}} Spin;\n",
+        align_of::<Quat>(),
+        align_of::<Vec4>()
+    );
+    assert!(header.contains(&spin), "{header}");
+    assert!(header.contains("typedef struct Difficulty {\n    uint8_t level;\n} Difficulty;\n"));
+}
+
+#[test]
+fn tags_have_no_struct_or_union_member() {
+    let header = header();
+    assert!(header.contains("// Marker is a tag, sent without a value\n"));
+    assert!(!header.contains("typedef struct Marker"));
+    assert!(header.contains(
+        "typedef union CustomComponents {\n    Spin Spin;\n    Difficulty Difficulty;\n} CustomComponents;\n"
+    ));
+    assert!(header.contains(
+        "typedef struct CustomData {\n    CustomType ty;\n    CustomComponents value;\n} CustomData;\n"
+    ));
+}
+
+#[test]
+fn resources_are_numbered_after_components() {
+    let header = header();
+    for (name, id) in [
+        ("Spin", CustomTypes::Spin as u8),
+        ("Marker", CustomTypes::Marker as u8),
+        ("Difficulty", CustomTypes::Difficulty as u8),
+    ] {
+        let define = format!("#define CustomType_{name} {id}\n");
+        assert!(header.contains(&define), "{define}");
+    }
+    assert_eq!(CustomTypes::Difficulty as u8, 2);
+}
@@ -0,0 +1,50 @@
+// not every test uses every helper
+#![allow(dead_code)]
+
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+
+use unrust::{Codegen, CodegenBackend, CodegenModel, GeneratedFiles, UnityBackend};
+
+/// Runs the unity backend on a test file, keeping the model and the C# it generated.
+pub fn generate(test_file: &str) -> anyhow::Result<(CodegenModel, GeneratedFiles)> {
+    generate_with(test_file, UnityBackend)
+}
+
+/// Runs `backend` on a test file, keeping the model and the files it generated.
+pub fn generate_with(
+    test_file: &str,
+    backend: impl CodegenBackend + 'static,
+) -> anyhow::Result<(CodegenModel, GeneratedFiles)> {
+    let root = Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("tests")
+        .join(test_file);
+    let output = tempfile::tempdir()?;
+
+    let captured = Arc::new(Mutex::new(None));
+    Codegen::new(root, output.path())
+        .only_backend(Capture(backend, captured.clone()), output.path())
+        .run()?;
+
+    let captured = captured.lock().unwrap().take();
+    Ok(captured.expect("the backend ran"))
+}
+
+struct Capture<B>(B, Arc<Mutex<Option<(CodegenModel, GeneratedFiles)>>>);
+
+impl<B: CodegenBackend> CodegenBackend for Capture<B> {
+    fn name(&self) -> &str {
+        "capture"
+    }
+
+    fn generate(
+        &self,
+        config: &Codegen,
+        model: &CodegenModel,
+        files: &mut GeneratedFiles,
+    ) -> anyhow::Result<()> {
+        self.0.generate(config, model, files)?;
+        *self.1.lock().unwrap() = Some((model.clone(), std::mem::take(files)));
+        Ok(())
+    }
+}
@@ -0,0 +1,83 @@
+use unrust::bevy;
+use unrust::bevy::prelude::*;
+use unrust::{
+    unity_authoring, unrust_setup, GamePlugin, PendingEntityRefs, UnityEntity, UnityEntityRef,
+    MAX_PENDING_FRAMES,
+};
+
+#[unity_authoring]
+pub struct Turret {
+    pub target: UnityEntityRef,
+    pub switches: Vec<UnityEntityRef>,
+}
+
+#[unrust_setup(components = [Turret])]
+pub fn setup(_app: &mut App) {}
+
+fn unity_entity(index: i32) -> UnityEntity {
+    UnityEntity { index, version: 1 }
+}
+
+fn app_with_turret() -> (App, Entity) {
+    let mut app = App::new();
+    Game.initialize(&mut app);
+
+    let turret = Turret {
+        target: UnityEntityRef::Pending(unity_entity(5)),
+        switches: vec![UnityEntityRef::Pending(unity_entity(7)), UnityEntityRef::None],
+    };
+    let mut entity = app.world.spawn_empty();
+    unrust::insert_unity_component(&mut entity, turret);
+    let id = entity.id();
+
+    (app, id)
+}
+
+#[test]
+fn resolves_refs_as_their_entities_spawn() {
+    let (mut app, turret) = app_with_turret();
+
+    let target = app.world.spawn(unity_entity(5)).id();
+    app.update();
+    let component = app.world.get::<Turret>(turret).unwrap();
+    assert_eq!(component.target.entity(), Some(target));
+    assert!(component.switches[0].is_pending());
+    assert_eq!(component.switches[1], UnityEntityRef::None);
+
+    let switch = app.world.spawn(unity_entity(7)).id();
+    app.update();
+    let component = app.world.get::<Turret>(turret).unwrap();
+    assert_eq!(component.switches[0].entity(), Some(switch));
+    assert!(app.world.get::<PendingEntityRefs<Turret>>(turret).is_none());
+}
+
+#[test]
+fn despawned_entities_are_not_resolved() {
+    let (mut app, turret) = app_with_turret();
+
+    let target = app.world.spawn(unity_entity(5)).id();
+    app.update();
+    app.world.despawn(target);
+    let switch = app.world.spawn(unity_entity(5)).id();
+    app.world.spawn(unity_entity(7));
+    app.update();
+
+    let index = app.world.resource::<unrust::UnityEntityIndex>();
+    assert_eq!(index.get(&unity_entity(5)), Some(switch));
+    assert!(app.world.get::<PendingEntityRefs<Turret>>(turret).is_none());
+}
+
+#[test]
+fn gives_up_on_entities_that_never_spawn() {
+    let (mut app, turret) = app_with_turret();
+
+    for _ in 1..MAX_PENDING_FRAMES {
+        app.update();
+    }
+    assert!(app.world.get::<PendingEntityRefs<Turret>>(turret).is_some());
+
+    app.update();
+    let component = app.world.get::<Turret>(turret).unwrap();
+    assert!(component.target.is_pending());
+    assert!(app.world.get::<PendingEntityRefs<Turret>>(turret).is_none());
+}
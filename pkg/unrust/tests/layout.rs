@@ -0,0 +1,184 @@
+use std::mem::{align_of, offset_of, size_of};
+
+use unrust::bevy;
+use unrust::bevy::prelude::*;
+use unrust::{unity_authoring, unrust_setup, FieldType, GamePlugin};
+
+mod common;
+
+#[unity_authoring]
+pub struct Primitives {
+    pub flag: bool,
+    pub a_u8: u8,
+    pub a_i8: i8,
+    pub a_u16: u16,
+    pub a_i16: i16,
+    pub letter: char,
+    pub a_f32: f32,
+    pub a_f64: f64,
+    pub a_i32: i32,
+    pub a_i64: i64,
+    pub a_u32: u32,
+    pub a_u64: u64,
+}
+
+#[unity_authoring]
+pub struct Padded {
+    pub flag: bool,
+    pub wide: i64,
+    pub narrow: u8,
+    pub letter: char,
+}
+
+#[unity_authoring]
+pub struct Maths {
+    pub flag: bool,
+    pub rotation: Quat,
+    pub offset: Vec3,
+}
+
+#[unity_authoring]
+pub struct Marker;
+
+#[unrust_setup(components = [Primitives, Padded, Maths, Marker])]
+pub fn setup(_app: &mut App) {}
+
+#[test]
+fn primitives_match_rust() {
+    macro_rules! check {
+        ($($variant:ident => $ty:ty),*) => {
+            $(
+                assert_eq!(FieldType::$variant.size(), size_of::<$ty>(), stringify!($ty));
+                assert_eq!(FieldType::$variant.align(), align_of::<$ty>(), stringify!($ty));
+            )*
+        };
+    }
+
+    check!(
+        Bool => bool, U8 => u8, I8 => i8, U16 => u16, I16 => i16, Char => char,
+        F32 => f32, F64 => f64, I32 => i32, I64 => i64, U32 => u32, U64 => u64
+    );
+}
+
+#[test]
+fn wire_structs_match_codegen() {
+    let (model, files) = common::generate("layout.rs").unwrap();
+    let hooks = files.get("UnrustHooks.cs").unwrap();
+
+    macro_rules! check {
+        ($($name:ident => $wire:ty { $($field:ident),* }),*) => {
+            $(
+                let component = model
+                    .components
+                    .iter()
+                    .find(|component| component.name == stringify!($name))
+                    .unwrap();
+                let layout = component.layout();
+                assert_eq!(layout.size, size_of::<$wire>(), stringify!($name));
+                assert_eq!(layout.align, align_of::<$wire>(), stringify!($name));
+                assert_eq!(layout.offsets, [$(offset_of!($wire, $field)),*], stringify!($name));
+
+                // unity asserts the C# struct has the size rust expects
+                let check = format!("SizeOf<{}>() == {}", stringify!($name), size_of::<$wire>());
+                assert!(hooks.contains(&check), "{check}");
+            )*
+        };
+    }
+
+    check!(
+        Primitives => PrimitivesUnrustWire {
+            flag, a_u8, a_i8, a_u16, a_i16, letter, a_f32, a_f64, a_i32, a_i64, a_u32, a_u64
+        },
+        Padded => PaddedUnrustWire { flag, wide, narrow, letter },
+        Maths => MathsUnrustWire { flag, rotation, offset }
+    );
+}
+
+#[test]
+fn custom_data_matches_codegen() {
+    let (model, files) = common::generate("layout.rs").unwrap();
+    let layout = model.custom_data_layout();
+    assert_eq!(layout.size, size_of::<CustomData>());
+    assert_eq!(layout.align, align_of::<CustomData>());
+    assert_eq!(
+        layout.offsets,
+        [offset_of!(CustomData, ty), offset_of!(CustomData, value)]
+    );
+
+    // Maths makes it 16 byte aligned, more than C# lays out on its own
+    let components = files.get("UnrustComponent.cs").unwrap();
+    for check in [
+        format!(
+            "[StructLayout(LayoutKind.Explicit, Size = {})]",
+            size_of::<CustomData>()
+        ),
+        "[FieldOffset(0)] public CustomType ty;".to_string(),
+        format!(
+            "[FieldOffset({})] public CustomComponents value;",
+            offset_of!(CustomData, value)
+        ),
+    ] {
+        assert!(components.contains(&check), "{check}");
+    }
+}
+
+#[test]
+fn bools_are_one_byte_in_csharp() {
+    let (_, files) = common::generate("layout.rs").unwrap();
+    let component = files.get("PrimitivesAuthoring.cs").unwrap();
+    assert!(component.contains("[MarshalAs(UnmanagedType.U1)] public bool flag;"));
+    assert!(component.contains("public uint letter;"));
+}
+
+#[test]
+fn wide_integers_are_rejected() {
+    let dir = tempfile::tempdir().unwrap();
+    let dir = dir.path();
+    std::fs::write(
+        dir.join("lib.rs"),
+        "#[unity_authoring]\npub struct Wide {\n    pub big: u128,\n    pub signed: i128,\n}\n",
+    )
+    .unwrap();
+
+    let result = unrust::Codegen::new(dir.join("lib.rs"), dir.join("out")).run();
+
+    let error = result.unwrap_err().to_string();
+    assert!(
+        error.contains("`Wide.big`, unity has no 128 bit integers"),
+        "{error}"
+    );
+    assert!(
+        error.contains("`Wide.signed`, unity has no 128 bit integers"),
+        "{error}"
+    );
+}
+
+#[test]
+fn reads_custom_data_unity_only_aligns_to_8() {
+    assert_eq!(align_of::<CustomData>(), 16);
+    let data = CustomData {
+        ty: CustomTypes::Maths,
+        value: CustomComponents {
+            Maths: MathsUnrustWire {
+                flag: 1,
+                rotation: Quat::from_rotation_y(1.0),
+                offset: Vec3::new(1.0, 2.0, 3.0),
+            },
+        },
+    };
+
+    // a managed C# array, 8 but not 16 byte aligned
+    let mut buffer = vec![0u64; size_of::<CustomData>() / 8 + 1];
+    let start = (buffer.as_ptr() as usize % 16 == 0) as usize * 8;
+    let custom = unsafe { buffer.as_mut_ptr().cast::<u8>().add(start) };
+    unsafe { custom.cast::<CustomData>().write_unaligned(data) };
+
+    let mut world = World::new();
+    let entity = world.spawn_empty().id();
+    unsafe { handle_custom_components(&mut world.entity_mut(entity), custom, 1) };
+
+    let maths = world.get::<Maths>(entity).unwrap();
+    assert!(maths.flag);
+    assert_eq!(maths.rotation, Quat::from_rotation_y(1.0));
+    assert_eq!(maths.offset, Vec3::new(1.0, 2.0, 3.0));
+}
@@ -0,0 +1,57 @@
+use std::mem::{align_of, size_of};
+
+use unrust::bevy;
+use unrust::bevy::prelude::*;
+use unrust::{unity_authoring, unrust_setup, CHeaderBackend, GamePlugin};
+
+mod common;
+
+#[unity_authoring]
+pub struct Player;
+
+#[unity_authoring]
+pub struct Enemy;
+
+#[unrust_setup(components = [Player, Enemy])]
+pub fn setup(_app: &mut App) {}
+
+#[test]
+fn custom_data_is_only_the_type() {
+    let (model, files) = common::generate("tags.rs").unwrap();
+    let layout = model.custom_data_layout();
+    assert_eq!(layout.size, size_of::<CustomData>());
+    assert_eq!(layout.align, align_of::<CustomData>());
+    assert_eq!(layout.offsets, [0]);
+    assert_eq!(size_of::<CustomData>(), 1);
+
+    let components = files.get("UnrustComponent.cs").unwrap();
+    assert!(components.contains(
+        "[StructLayout(LayoutKind.Sequential)] public struct CustomData { public CustomType ty; }"
+    ));
+    assert!(!components.contains("CustomComponents value"));
+}
+
+#[test]
+fn c_header_has_no_empty_union() {
+    let (_, files) = common::generate_with("tags.rs", CHeaderBackend).unwrap();
+    let header = files.get("unrust.h").unwrap();
+    assert!(header.contains("typedef struct CustomData {\n    CustomType ty;\n} CustomData;\n"));
+    assert!(!header.contains("CustomComponents"));
+    assert!(header.contains("#define CustomType_Player 0\n#define CustomType_Enemy 1\n"));
+}
+
+#[test]
+fn tags_are_spawned() {
+    let data = CustomData {
+        ty: CustomTypes::Enemy,
+        value: CustomComponents { Enemy: Enemy },
+    };
+
+    let mut world = World::new();
+    let entity = world.spawn_empty().id();
+    let custom = &data as *const CustomData as *const u8;
+    unsafe { handle_custom_components(&mut world.entity_mut(entity), custom, 1) };
+
+    assert!(world.get::<Enemy>(entity).is_some());
+    assert!(world.get::<Player>(entity).is_none());
+}
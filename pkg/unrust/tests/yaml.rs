@@ -0,0 +1,338 @@
+use unrust::bevy::prelude::{Quat, Vec3, Vec4};
+use unrust::{UnityFileRef, UnityYaml, UnityYamlFile};
+
+/// From the example's `Prefabs/Cube.prefab`, with the child and some renderer fields left out.
+const CUBE_PREFAB: &str = "%YAML 1.1
+%TAG !u! tag:unity3d.com,2011:
+--- !u!1 &3111494636856780050
+GameObject:
+  m_ObjectHideFlags: 0
+  m_CorrespondingSourceObject: {fileID: 0}
+  m_PrefabInstance: {fileID: 0}
+  m_PrefabAsset: {fileID: 0}
+  serializedVersion: 6
+  m_Component:
+  - component: {fileID: 9203637124288179998}
+  - component: {fileID: 3226660776436602714}
+  - component: {fileID: 7593087731519675482}
+  m_Layer: 0
+  m_Name: Cube
+  m_TagString: Untagged
+  m_Icon: {fileID: 0}
+  m_IsActive: 1
+--- !u!4 &9203637124288179998
+Transform:
+  m_ObjectHideFlags: 0
+  m_GameObject: {fileID: 3111494636856780050}
+  m_LocalRotation: {x: -0, y: -0, z: -0, w: 1}
+  m_LocalPosition: {x: 0, y: 2, z: 0}
+  m_LocalScale: {x: 0.5, y: 0.5, z: 0.5}
+  m_ConstrainProportionsScale: 0
+  m_Children: []
+  m_Father: {fileID: 0}
+  m_RootOrder: -1
+--- !u!23 &3226660776436602714
+MeshRenderer:
+  m_ObjectHideFlags: 0
+  m_GameObject: {fileID: 3111494636856780050}
+  m_Enabled: 1
+  m_Materials:
+  - {fileID: 2100000, guid: 02b1acfdb8e25329ca3dcfb9f28a7fda, type: 2}
+  m_StaticBatchInfo:
+    firstSubMesh: 0
+    subMeshCount: 0
+  m_AutoUVMaxDistance: 0.5
+--- !u!114 &7593087731519675482
+MonoBehaviour:
+  m_ObjectHideFlags: 0
+  m_GameObject: {fileID: 3111494636856780050}
+  m_Enabled: 1
+  m_EditorHideFlags: 0
+  m_Script: {fileID: 11500000, guid: a455bbecf3a6afa65a77e3e94799392a, type: 3}
+  m_Name: 
+  m_EditorClassIdentifier: 
+  speed: 0.3
+";
+
+/// A scene holding an instance of the cube prefab, unity only writes the fields it overrides.
+const PREFAB_INSTANCE: &str = "%YAML 1.1
+%TAG !u! tag:unity3d.com,2011:
+--- !u!1001 &1838406385
+PrefabInstance:
+  m_ObjectHideFlags: 0
+  serializedVersion: 2
+  m_Modification:
+    serializedVersion: 3
+    m_TransformParent: {fileID: 0}
+    m_Modifications:
+    - target: {fileID: 3111494636856780050, guid: ff2a6f8f24bef6774ae11c3b74f82424, type: 3}
+      propertyPath: m_Name
+      value: Cube (1)
+      objectReference: {fileID: 0}
+    - target: {fileID: 7593087731519675482, guid: ff2a6f8f24bef6774ae11c3b74f82424, type: 3}
+      propertyPath: m_EditorClassIdentifier
+      value: 
+      objectReference: {fileID: 0}
+    m_RemovedComponents: []
+    m_RemovedGameObjects: []
+  m_SourcePrefab: {fileID: 100100000, guid: ff2a6f8f24bef6774ae11c3b74f82424, type: 3}
+--- !u!4 &1838406386 stripped
+Transform:
+  m_CorrespondingSourceObject: {fileID: 9203637124288179998, guid: ff2a6f8f24bef6774ae11c3b74f82424, type: 3}
+  m_PrefabInstance: {fileID: 1838406385}
+  m_PrefabAsset: {fileID: 0}
+--- !u!1 &-4216859302048453862 stripped
+GameObject:
+  m_CorrespondingSourceObject: {fileID: 3111494636856780050, guid: ff2a6f8f24bef6774ae11c3b74f82424, type: 3}
+  m_PrefabInstance: {fileID: 1838406385}
+  m_PrefabAsset: {fileID: 0}
+";
+
+/// From the example's `ProjectSettings/TagManager.asset`, unnamed layers are empty values.
+const TAG_MANAGER: &str = "%YAML 1.1
+%TAG !u! tag:unity3d.com,2011:
+--- !u!78 &1
+TagManager:
+  serializedVersion: 2
+  tags: []
+  layers:
+  - Default
+  - TransparentFX
+  - Ignore Raycast
+  - 
+  - Water
+  - UI
+  - 
+  m_SortingLayers:
+  - name: Default
+    uniqueID: 0
+    locked: 0
+";
+
+/// A ScriptableObject `.asset` with the strings unity quotes and wraps.
+const LEVEL_ASSET: &str = "%YAML 1.1
+%TAG !u! tag:unity3d.com,2011:
+--- !u!114 &11400000
+MonoBehaviour:
+  m_ObjectHideFlags: 0
+  m_CorrespondingSourceObject: {fileID: 0}
+  m_PrefabInstance: {fileID: 0}
+  m_PrefabAsset: {fileID: 0}
+  m_GameObject: {fileID: 0}
+  m_Enabled: 1
+  m_EditorHideFlags: 0
+  m_Script: {fileID: 11500000, guid: 5a9e0b1c2d3f4a5b6c7d8e9f0a1b2c3d, type: 3}
+  m_Name: Level 1
+  m_EditorClassIdentifier: 
+  title: 'it''s a \"long\" title that unity
+    wrapped onto the next line'
+  subtitle: \"caf\\u00E9 \\U0001F600 a\\
+    \\ b\"
+  waves:
+  - count: 3
+    spawn: {x: 1, y: 0, z: -2.5}
+  - count: 5
+    spawn: {x: 4, y: 0, z: 6}
+  tint: {r: 1, g: 0.5, b: 0, a: 1}
+  bosses: []
+";
+
+#[test]
+fn prefab_objects_and_references() {
+    let file = UnityYamlFile::parse(CUBE_PREFAB).unwrap();
+    assert_eq!(file.documents.len(), 4);
+
+    let cube = file.documents_of("GameObject").next().unwrap();
+    assert_eq!((cube.class_id, cube.file_id), (1, 3111494636856780050));
+    assert!(!cube.stripped);
+    assert_eq!(cube.value.field::<String>("m_Name").unwrap(), "Cube");
+    assert_eq!(cube.value.field::<bool>("m_IsActive"), Some(true));
+
+    let components: Vec<UnityYaml> = cube.value.field("m_Component").unwrap();
+    let types: Vec<_> = components
+        .iter()
+        .map(|component| {
+            let reference: UnityFileRef = component.field("component").unwrap();
+            file.resolve(&reference).unwrap().type_name.as_str()
+        })
+        .collect();
+    assert_eq!(types, ["Transform", "MeshRenderer", "MonoBehaviour"]);
+
+    let transform = file.document(9203637124288179998).unwrap();
+    assert_eq!(
+        transform.value.field::<Quat>("m_LocalRotation"),
+        Some(Quat::IDENTITY)
+    );
+    assert_eq!(
+        transform.value.field("m_LocalPosition"),
+        Some(Vec3::new(0.0, 2.0, 0.0))
+    );
+    assert_eq!(transform.value.field::<i32>("m_RootOrder"), Some(-1));
+    let father: UnityFileRef = transform.value.field("m_Father").unwrap();
+    assert!(father.is_null());
+    assert!(file.resolve(&father).is_none());
+
+    let renderer = file.documents_of("MeshRenderer").next().unwrap();
+    let materials: Vec<UnityFileRef> = renderer.value.field("m_Materials").unwrap();
+    assert_eq!(
+        materials,
+        [UnityFileRef {
+            file_id: 2100000,
+            guid: Some("02b1acfdb8e25329ca3dcfb9f28a7fda".to_string()),
+            ty: Some(2),
+        }]
+    );
+    // in another file
+    assert!(file.resolve(&materials[0]).is_none());
+    let batch = renderer.value.get("m_StaticBatchInfo").unwrap();
+    assert_eq!(batch.field::<u32>("subMeshCount"), Some(0));
+
+    let script = file.documents_of("MonoBehaviour").next().unwrap();
+    assert_eq!(script.value.field::<f32>("speed"), Some(0.3));
+}
+
+#[test]
+fn stripped_documents_of_a_prefab_instance() {
+    let file = UnityYamlFile::parse(PREFAB_INSTANCE).unwrap();
+
+    let stripped: Vec<_> = file.documents.iter().filter(|doc| doc.stripped).collect();
+    assert_eq!(stripped.len(), 2);
+    assert_eq!(
+        (
+            stripped[0].class_id,
+            stripped[0].file_id,
+            stripped[0].type_name.as_str()
+        ),
+        (4, 1838406386, "Transform")
+    );
+    assert_eq!(
+        (
+            stripped[1].class_id,
+            stripped[1].file_id,
+            stripped[1].type_name.as_str()
+        ),
+        (1, -4216859302048453862, "GameObject")
+    );
+
+    let source: UnityFileRef = stripped[1]
+        .value
+        .field("m_CorrespondingSourceObject")
+        .unwrap();
+    assert_eq!(source.file_id, 3111494636856780050);
+    assert_eq!(
+        source.guid.as_deref(),
+        Some("ff2a6f8f24bef6774ae11c3b74f82424")
+    );
+    let instance: UnityFileRef = stripped[1].value.field("m_PrefabInstance").unwrap();
+    assert_eq!(file.resolve(&instance).unwrap().type_name, "PrefabInstance");
+
+    let modification = file.documents[0].value.get("m_Modification").unwrap();
+    let modifications = modification.get("m_Modifications").unwrap();
+    let name = modifications.at(0).unwrap();
+    assert_eq!(name.field::<String>("propertyPath").unwrap(), "m_Name");
+    assert_eq!(name.field::<String>("value").unwrap(), "Cube (1)");
+    assert_eq!(
+        modifications
+            .at(1)
+            .unwrap()
+            .field::<String>("value")
+            .unwrap(),
+        ""
+    );
+    assert_eq!(
+        modification.field::<Vec<UnityYaml>>("m_RemovedComponents"),
+        Some(vec![])
+    );
+}
+
+#[test]
+fn empty_values() {
+    let file = UnityYamlFile::parse(TAG_MANAGER).unwrap();
+    let tags = &file.documents[0].value;
+    assert_eq!(tags.field::<Vec<String>>("tags"), Some(vec![]));
+    assert_eq!(
+        tags.field::<Vec<String>>("layers").unwrap(),
+        [
+            "Default",
+            "TransparentFX",
+            "Ignore Raycast",
+            "",
+            "Water",
+            "UI",
+            ""
+        ]
+    );
+    let sorting = tags.get("m_SortingLayers").unwrap().at(0).unwrap();
+    assert_eq!(sorting.field::<String>("name").unwrap(), "Default");
+    assert_eq!(sorting.field::<bool>("locked"), Some(false));
+
+    let file = UnityYamlFile::parse(CUBE_PREFAB).unwrap();
+    let script = file.documents_of("MonoBehaviour").next().unwrap();
+    assert_eq!(script.value.field::<String>("m_Name").unwrap(), "");
+    assert_eq!(
+        script
+            .value
+            .get("m_EditorClassIdentifier")
+            .unwrap()
+            .as_str(),
+        Some("")
+    );
+    assert!(script.value.field::<f32>("m_Name").is_none());
+}
+
+#[test]
+fn scriptable_object_asset() {
+    let file = UnityYamlFile::parse(LEVEL_ASSET).unwrap();
+    let level = &file.documents[0];
+    assert_eq!((level.class_id, level.file_id), (114, 11400000));
+
+    let script: UnityFileRef = level.value.field("m_Script").unwrap();
+    assert_eq!(
+        script.guid.as_deref(),
+        Some("5a9e0b1c2d3f4a5b6c7d8e9f0a1b2c3d")
+    );
+    assert_eq!(level.value.field::<String>("m_Name").unwrap(), "Level 1");
+    assert_eq!(
+        level.value.field::<String>("title").unwrap(),
+        "it's a \"long\" title that unity wrapped onto the next line"
+    );
+    assert_eq!(
+        level.value.field::<String>("subtitle").unwrap(),
+        "café 😀 a b"
+    );
+
+    let waves = level.value.get("waves").unwrap();
+    assert_eq!(waves.at(0).unwrap().field::<u32>("count"), Some(3));
+    assert_eq!(
+        waves.at(1).unwrap().field("spawn"),
+        Some(Vec3::new(4.0, 0.0, 6.0))
+    );
+    assert_eq!(
+        waves.at(0).unwrap().field("spawn"),
+        Some(Vec3::new(1.0, 0.0, -2.5))
+    );
+    assert!(waves.at(2).is_none());
+    assert_eq!(
+        level.value.field("tint"),
+        Some(Vec4::new(1.0, 0.5, 0.0, 1.0))
+    );
+    assert_eq!(level.value.field::<Vec<u32>>("bosses"), Some(vec![]));
+}
+
+#[test]
+fn unicode_whitespace_in_flow_mappings() {
+    let text = "%YAML 1.1\n--- !u!4 &1\nTransform:\n  m_Offset: {x: \u{a0}1, y:\u{3000}2}\n";
+    let file = UnityYamlFile::parse(text).unwrap();
+    let offset = file.documents[0].value.get("m_Offset").unwrap();
+    assert_eq!(offset.field::<f32>("x"), Some(1.0));
+    assert_eq!(offset.field::<f32>("y"), Some(2.0));
+}
+
+#[test]
+fn rejects_binary_and_broken_files() {
+    let err = UnityYamlFile::parse("\0\0\0\0binary").unwrap_err();
+    assert!(err.to_string().contains("Force Text"), "{err}");
+
+    let broken = "%YAML 1.1\n--- !u!1 &1\nGameObject:\n  m_Icon: {fileID: 0\n";
+    assert!(UnityYamlFile::parse(broken).is_err());
+}
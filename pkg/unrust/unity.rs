@@ -2,13 +2,18 @@ use std::{sync::Arc, time::Duration};
 
 use bevy::{
     app::PluginGroupBuilder,
-    asset::{AssetPlugin, ChangeWatcher},
+    asset::{AddAsset, AssetPlugin, ChangeWatcher},
     prelude::*,
     tasks::tick_global_task_pools_on_main_thread,
     time::TimePlugin,
 };
 use inbuilt::*;
 
+use crate::meta::{
+    scan_meta_files, update_asset_index, UnityAssetIndex, UnityMeta, UnityMetaLoader,
+};
+use crate::yaml::{UnityYamlFile, UnityYamlLoader};
+
 pub type UpdateFn = extern "C" fn(data: *const InbuiltEntityData, len: usize);
 pub type CreateFn = extern "C" fn(data: *const InbuiltEntityData, len: usize);
 pub type DestroyFn = extern "C" fn(entity: *const UnityEntity, len: usize);
@@ -76,6 +81,11 @@ impl Plugin for UnityPlugins {
                 asset_folder: self.base_path.clone(),
                 watch_for_changes: ChangeWatcher::with_delay(Duration::from_millis(200)),
             })
+            .add_asset::<UnityYamlFile>()
+            .init_asset_loader::<UnityYamlLoader>()
+            .add_asset::<UnityMeta>()
+            .init_asset_loader::<UnityMetaLoader>()
+            .init_resource::<UnityAssetIndex>()
             .insert_non_send_resource(CallbacksNonSend {
                 create_fn,
                 update_fn,
@@ -88,7 +98,9 @@ impl Plugin for UnityPlugins {
                     update_unity_system,
                     destroy_unity_system,
                 ),
-            );
+            )
+            .add_systems(Startup, scan_meta_files)
+            .add_systems(PreUpdate, update_asset_index);
     }
 }
 
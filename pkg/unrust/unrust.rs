@@ -2,18 +2,27 @@ use std::ffi::{c_char, CStr, CString};
 
 mod loader;
 mod logger;
+mod meta;
 mod unity;
+mod yaml;
 
 pub use bevy;
 
 use bevy::prelude::*;
-pub use codegen::generate_csharp;
+pub use codegen::{
+    check_csharp, generate_csharp, CHeaderBackend, Codegen, CodegenBackend, CodegenModel,
+    FieldType, GeneratedFiles, Generator, Layout, OutputLayout, TransformUsage, UnityBackend,
+};
 pub use inbuilt::*;
 pub use loader::GamePlugin;
+pub use meta::{UnityAssetIndex, UnityMeta, UnityMetaLoader};
 pub use tracing;
 
 pub use unity::{CreateFn, DestroyEntity, DestroyFn, InstantiateEntity, PrefabData, UpdateFn};
 pub use unrust_proc_macro::*;
+pub use yaml::{
+    FromUnityYaml, UnityDocument, UnityFileRef, UnityYaml, UnityYamlFile, UnityYamlLoader,
+};
 
 use crate::{
     logger::{setup_logging, teardown_logging, LoggerFunc},
@@ -0,0 +1,656 @@
+use anyhow::{bail, Context, Result};
+use bevy::{
+    asset::{AssetLoader, LoadContext, LoadedAsset},
+    prelude::*,
+    reflect::{TypePath, TypeUuid},
+    utils::BoxedFuture,
+};
+
+/// A unity file in its text serialization, eg: a `.asset` ScriptableObject, a `.prefab` or a
+/// `.unity` scene, as the objects it holds.
+#[derive(Debug, Clone, TypeUuid, TypePath)]
+#[uuid = "7d1c1f4e-3c0a-4b59-9a57-1f3f5d8e2b61"]
+pub struct UnityYamlFile {
+    pub documents: Vec<UnityDocument>,
+}
+
+/// One `--- !u!<class> &<fileID>` section of a [`UnityYamlFile`], a single object.
+#[derive(Debug, Clone, PartialEq)]
+pub struct UnityDocument {
+    /// The kind of object, eg: 1 for a GameObject or 114 for a MonoBehaviour.
+    pub class_id: i32,
+    /// What `{fileID: ..}` references inside the file point at.
+    pub file_id: i64,
+    /// Part of a prefab instance, with only the fields the scene overrides.
+    pub stripped: bool,
+    /// The single key of the document, eg: `GameObject` or `MonoBehaviour`.
+    pub type_name: String,
+    /// The serialized fields of the object.
+    pub value: UnityYaml,
+}
+
+/// A value of a [`UnityDocument`]. Unity writes every scalar the same way whatever its
+/// type, so scalars are kept as text and converted with [`UnityYaml::to`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum UnityYaml {
+    Scalar(String),
+    Sequence(Vec<UnityYaml>),
+    /// The keys in the order unity wrote them.
+    Mapping(Vec<(String, UnityYaml)>),
+}
+
+/// A `{fileID: .., guid: .., type: ..}` reference to an object, in the same file when it has
+/// no guid.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnityFileRef {
+    pub file_id: i64,
+    /// The guid from the `.meta` file of the asset holding the object.
+    pub guid: Option<String>,
+    pub ty: Option<i32>,
+}
+
+impl UnityFileRef {
+    /// A reference to nothing, `{fileID: 0}`.
+    pub fn is_null(&self) -> bool {
+        self.file_id == 0
+    }
+}
+
+impl UnityYamlFile {
+    pub fn parse(text: &str) -> Result<UnityYamlFile> {
+        let text = text.trim_start_matches('\u{feff}');
+        if !text.starts_with("%YAML") {
+            bail!(
+                "not a unity yaml file, binary assets need Asset Serialization set to Force Text"
+            );
+        }
+
+        let mut documents = vec![];
+        let mut header: Option<(usize, &str)> = None;
+        let mut body = vec![];
+        for (index, line) in text.lines().enumerate() {
+            if let Some(next) = line.strip_prefix("--- ") {
+                if let Some(header) = header.replace((index + 1, next)) {
+                    documents.push(parse_document(header, &body)?);
+                }
+                body.clear();
+            } else if header.is_some() {
+                body.push((index + 1, line));
+            }
+        }
+        if let Some(header) = header {
+            documents.push(parse_document(header, &body)?);
+        }
+
+        Ok(UnityYamlFile { documents })
+    }
+
+    /// The object with `file_id`.
+    pub fn document(&self, file_id: i64) -> Option<&UnityDocument> {
+        self.documents.iter().find(|doc| doc.file_id == file_id)
+    }
+
+    /// Every object of a type, eg: `"MonoBehaviour"` for the values of a ScriptableObject.
+    pub fn documents_of<'a>(
+        &'a self,
+        type_name: &'a str,
+    ) -> impl Iterator<Item = &'a UnityDocument> + 'a {
+        self.documents
+            .iter()
+            .filter(move |doc| doc.type_name == type_name)
+    }
+
+    /// The object `reference` points at, if it is in this file.
+    pub fn resolve(&self, reference: &UnityFileRef) -> Option<&UnityDocument> {
+        match (&reference.guid, reference.is_null()) {
+            (None, false) => self.document(reference.file_id),
+            _ => None,
+        }
+    }
+}
+
+impl UnityYaml {
+    /// The value of `key` in a mapping.
+    pub fn get(&self, key: &str) -> Option<&UnityYaml> {
+        match self {
+            UnityYaml::Mapping(entries) => entries
+                .iter()
+                .find_map(|(name, value)| (name == key).then_some(value)),
+            _ => None,
+        }
+    }
+
+    /// The element at `index` of a sequence.
+    pub fn at(&self, index: usize) -> Option<&UnityYaml> {
+        match self {
+            UnityYaml::Sequence(items) => items.get(index),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            UnityYaml::Scalar(value) => Some(value),
+            _ => None,
+        }
+    }
+
+    /// Converts the value, `None` if it does not hold a `T`.
+    pub fn to<T: FromUnityYaml>(&self) -> Option<T> {
+        T::from_unity_yaml(self)
+    }
+
+    /// Converts the value of `key` in a mapping, eg: `doc.value.field::<f32>("speed")`.
+    pub fn field<T: FromUnityYaml>(&self, key: &str) -> Option<T> {
+        self.get(key)?.to()
+    }
+}
+
+/// A type a [`UnityYaml`] value can be converted to, implement it to read your own types.
+pub trait FromUnityYaml: Sized {
+    fn from_unity_yaml(value: &UnityYaml) -> Option<Self>;
+}
+
+macro_rules! from_scalar {
+    ($($ty:ty),*) => {
+        $(
+            impl FromUnityYaml for $ty {
+                fn from_unity_yaml(value: &UnityYaml) -> Option<Self> {
+                    value.as_str()?.parse().ok()
+                }
+            }
+        )*
+    };
+}
+
+from_scalar!(u8, i8, u16, i16, u32, i32, u64, i64, f32, f64, String);
+
+impl FromUnityYaml for UnityYaml {
+    fn from_unity_yaml(value: &UnityYaml) -> Option<Self> {
+        Some(value.clone())
+    }
+}
+
+impl FromUnityYaml for bool {
+    fn from_unity_yaml(value: &UnityYaml) -> Option<Self> {
+        match value.as_str()? {
+            "1" | "true" => Some(true),
+            "0" | "false" => Some(false),
+            _ => None,
+        }
+    }
+}
+
+impl<T: FromUnityYaml> FromUnityYaml for Vec<T> {
+    fn from_unity_yaml(value: &UnityYaml) -> Option<Self> {
+        match value {
+            UnityYaml::Sequence(items) => items.iter().map(T::from_unity_yaml).collect(),
+            _ => None,
+        }
+    }
+}
+
+impl FromUnityYaml for Vec2 {
+    fn from_unity_yaml(value: &UnityYaml) -> Option<Self> {
+        Some(Vec2::new(value.field("x")?, value.field("y")?))
+    }
+}
+
+impl FromUnityYaml for Vec3 {
+    fn from_unity_yaml(value: &UnityYaml) -> Option<Self> {
+        Some(Vec3::new(
+            value.field("x")?,
+            value.field("y")?,
+            value.field("z")?,
+        ))
+    }
+}
+
+/// Also reads colors, `{r: .., g: .., b: .., a: ..}`.
+impl FromUnityYaml for Vec4 {
+    fn from_unity_yaml(value: &UnityYaml) -> Option<Self> {
+        match value.get("x") {
+            Some(_) => Some(Vec4::new(
+                value.field("x")?,
+                value.field("y")?,
+                value.field("z")?,
+                value.field("w")?,
+            )),
+            None => Some(Vec4::new(
+                value.field("r")?,
+                value.field("g")?,
+                value.field("b")?,
+                value.field("a")?,
+            )),
+        }
+    }
+}
+
+impl FromUnityYaml for Quat {
+    fn from_unity_yaml(value: &UnityYaml) -> Option<Self> {
+        Some(Quat::from_xyzw(
+            value.field("x")?,
+            value.field("y")?,
+            value.field("z")?,
+            value.field("w")?,
+        ))
+    }
+}
+
+impl FromUnityYaml for UnityFileRef {
+    fn from_unity_yaml(value: &UnityYaml) -> Option<Self> {
+        Some(UnityFileRef {
+            file_id: value.field("fileID")?,
+            guid: value.field("guid"),
+            ty: value.field("type"),
+        })
+    }
+}
+
+/// Loads `.asset`, `.prefab` and `.unity` files as a [`UnityYamlFile`].
+#[derive(Default)]
+pub struct UnityYamlLoader;
+
+impl AssetLoader for UnityYamlLoader {
+    fn load<'a>(
+        &'a self,
+        bytes: &'a [u8],
+        load_context: &'a mut LoadContext,
+    ) -> BoxedFuture<'a, Result<(), bevy::asset::Error>> {
+        Box::pin(async move {
+            let text = std::str::from_utf8(bytes)?;
+            let file = UnityYamlFile::parse(text)
+                .with_context(|| format!("can not read {}", load_context.path().display()))?;
+            load_context.set_default_asset(LoadedAsset::new(file));
+            Ok(())
+        })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["asset", "prefab", "unity"]
+    }
+}
+
+/// A line of a document without its indentation.
+#[derive(Clone, Copy)]
+struct Line<'a> {
+    number: usize,
+    indent: usize,
+    text: &'a str,
+    /// Empty lines are line breaks inside a scalar that continues on this line.
+    after_blank: bool,
+}
+
+fn parse_document(
+    (number, header): (usize, &str),
+    body: &[(usize, &str)],
+) -> Result<UnityDocument> {
+    // eg: `!u!114 &11400000` or `!u!1 &2 stripped`
+    let mut parts = header.split_whitespace();
+    let class_id = parts
+        .next()
+        .and_then(|tag| tag.strip_prefix("!u!")?.parse().ok());
+    let file_id = parts
+        .next()
+        .and_then(|anchor| anchor.strip_prefix('&')?.parse().ok());
+    let (Some(class_id), Some(file_id)) = (class_id, file_id) else {
+        bail!("line {number}: expected a document header like `--- !u!114 &11400000`");
+    };
+    let stripped = parts.next() == Some("stripped");
+
+    let mut lines = vec![];
+    let mut after_blank = false;
+    for &(number, line) in body {
+        let text = line.trim();
+        if text.is_empty() {
+            after_blank = true;
+            continue;
+        }
+        lines.push(Line {
+            number,
+            indent: line.len() - line.trim_start().len(),
+            text,
+            after_blank,
+        });
+        after_blank = false;
+    }
+
+    let mut parser = Parser { lines, pos: 0 };
+    let root = match parser.lines.is_empty() {
+        true => UnityYaml::Mapping(vec![]),
+        false => parser.block()?,
+    };
+    if let Some(line) = parser.lines.get(parser.pos) {
+        bail!("line {}: unexpected indentation", line.number);
+    }
+
+    let (type_name, value) = match root {
+        UnityYaml::Mapping(mut entries) if entries.len() == 1 => entries.remove(0),
+        _ => bail!("line {number}: expected the document to hold a single object"),
+    };
+
+    Ok(UnityDocument {
+        class_id,
+        file_id,
+        stripped,
+        type_name,
+        value,
+    })
+}
+
+/// The block style unity writes: mappings and sequences by indentation, with the values on
+/// the same line being scalars or flow collections.
+struct Parser<'a> {
+    lines: Vec<Line<'a>>,
+    pos: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn peek(&self) -> Option<Line<'a>> {
+        self.lines.get(self.pos).copied()
+    }
+
+    /// The mapping or sequence starting at the next line.
+    fn block(&mut self) -> Result<UnityYaml> {
+        let Some(line) = self.peek() else {
+            return Ok(UnityYaml::Scalar(String::new()));
+        };
+        match is_sequence_item(line.text) {
+            true => self.sequence(line.indent),
+            false => self.mapping(line.indent),
+        }
+    }
+
+    fn mapping(&mut self, indent: usize) -> Result<UnityYaml> {
+        let mut entries = vec![];
+        while let Some(line) = self.peek() {
+            if line.indent != indent || is_sequence_item(line.text) {
+                break;
+            }
+            let Some((key, value)) = split_entry(line.text) else {
+                bail!("line {}: expected `key: value`", line.number);
+            };
+            self.pos += 1;
+
+            let value = match self.peek() {
+                _ if !value.is_empty() => {
+                    let text = self.continued(value, indent);
+                    parse_inline(&text, line.number)?
+                }
+                // nested values start on the next line
+                Some(next) if next.indent > indent => self.block()?,
+                // unity writes sequences at the same indentation as their key
+                Some(next) if next.indent == indent && is_sequence_item(next.text) => {
+                    self.sequence(indent)?
+                }
+                _ => UnityYaml::Scalar(String::new()),
+            };
+            entries.push((unquote(key, line.number)?, value));
+        }
+
+        Ok(UnityYaml::Mapping(entries))
+    }
+
+    fn sequence(&mut self, indent: usize) -> Result<UnityYaml> {
+        let mut items = vec![];
+        while let Some(line) = self.peek() {
+            if line.indent != indent || !is_sequence_item(line.text) {
+                break;
+            }
+            let rest = line.text[1..].trim_start();
+
+            if rest.is_empty() {
+                self.pos += 1;
+                items.push(match self.peek() {
+                    Some(next) if next.indent > indent => self.block()?,
+                    _ => UnityYaml::Scalar(String::new()),
+                });
+            } else if split_entry(rest).is_some() {
+                // `- key: value` starts a mapping indented to its first key
+                let indent = line.indent + line.text.len() - rest.len();
+                self.lines[self.pos] = Line {
+                    indent,
+                    text: rest,
+                    ..line
+                };
+                items.push(self.mapping(indent)?);
+            } else {
+                self.pos += 1;
+                let text = self.continued(rest, indent);
+                items.push(parse_inline(&text, line.number)?);
+            }
+        }
+
+        Ok(UnityYaml::Sequence(items))
+    }
+
+    /// `first` and the lines indented further, which unity wraps long scalars onto.
+    fn continued(&mut self, first: &str, indent: usize) -> String {
+        let mut text = first.to_string();
+        while let Some(line) = self.peek().filter(|line| line.indent > indent) {
+            self.pos += 1;
+            // an escaped line break in a double quoted scalar joins without a space
+            if text.starts_with('"') && text.ends_with('\\') && !text.ends_with("\\\\") {
+                text.pop();
+            } else {
+                text.push(if line.after_blank { '\n' } else { ' ' });
+            }
+            text.push_str(line.text);
+        }
+
+        text
+    }
+}
+
+fn is_sequence_item(text: &str) -> bool {
+    text == "-" || text.starts_with("- ")
+}
+
+/// `key: value` or `key:`, unless the text is a flow collection or quoted scalar.
+fn split_entry(text: &str) -> Option<(&str, &str)> {
+    if text.starts_with(['{', '[', '"', '\'']) {
+        return None;
+    }
+    if let Some(key) = text.strip_suffix(':') {
+        if !key.contains(": ") {
+            return Some((key, ""));
+        }
+    }
+    let (key, value) = text.split_once(": ")?;
+    Some((key, value.trim()))
+}
+
+fn parse_inline(text: &str, number: usize) -> Result<UnityYaml> {
+    let mut flow = Flow {
+        text,
+        pos: 0,
+        number,
+    };
+    let value = flow.value(false)?;
+    flow.skip_whitespace();
+    if flow.pos != text.len() {
+        bail!("line {number}: unexpected `{}`", &text[flow.pos..]);
+    }
+
+    Ok(value)
+}
+
+fn unquote(text: &str, number: usize) -> Result<String> {
+    match parse_inline(text, number)? {
+        UnityYaml::Scalar(value) => Ok(value),
+        _ => bail!("line {number}: expected a scalar key"),
+    }
+}
+
+/// Flow collections, `{a: 1, b: [2, 3]}`, and scalars.
+struct Flow<'a> {
+    text: &'a str,
+    pos: usize,
+    number: usize,
+}
+
+impl Flow<'_> {
+    fn peek(&self) -> Option<char> {
+        self.text[self.pos..].chars().next()
+    }
+
+    fn skip_whitespace(&mut self) {
+        while let Some(c) = self.peek().filter(|c| c.is_whitespace()) {
+            self.pos += c.len_utf8();
+        }
+    }
+
+    fn expect(&mut self, expected: char) -> Result<()> {
+        self.skip_whitespace();
+        match self.peek() {
+            Some(c) if c == expected => {
+                self.pos += 1;
+                Ok(())
+            }
+            _ => bail!("line {}: expected `{expected}`", self.number),
+        }
+    }
+
+    /// A value, which inside a flow collection ends at `,`, `}` or `]`.
+    fn value(&mut self, in_flow: bool) -> Result<UnityYaml> {
+        self.skip_whitespace();
+        match self.peek() {
+            Some('{') => self.mapping(),
+            Some('[') => self.sequence(),
+            Some('\'') => self.single_quoted().map(UnityYaml::Scalar),
+            Some('"') => self.double_quoted().map(UnityYaml::Scalar),
+            _ => Ok(UnityYaml::Scalar(self.plain(in_flow, false))),
+        }
+    }
+
+    fn mapping(&mut self) -> Result<UnityYaml> {
+        self.pos += 1;
+        let mut entries = vec![];
+        loop {
+            self.skip_whitespace();
+            if self.peek() == Some('}') {
+                self.pos += 1;
+                return Ok(UnityYaml::Mapping(entries));
+            }
+            let key = match self.peek() {
+                Some('\'') => self.single_quoted()?,
+                Some('"') => self.double_quoted()?,
+                _ => self.plain(true, true),
+            };
+            self.expect(':')?;
+            entries.push((key, self.value(true)?));
+            self.separator('}')?;
+        }
+    }
+
+    fn sequence(&mut self) -> Result<UnityYaml> {
+        self.pos += 1;
+        let mut items = vec![];
+        loop {
+            self.skip_whitespace();
+            if self.peek() == Some(']') {
+                self.pos += 1;
+                return Ok(UnityYaml::Sequence(items));
+            }
+            items.push(self.value(true)?);
+            self.separator(']')?;
+        }
+    }
+
+    /// Skips the `,` after an entry, or stops before the `close` of the collection.
+    fn separator(&mut self, close: char) -> Result<()> {
+        self.skip_whitespace();
+        match self.peek() {
+            Some(',') => {
+                self.pos += 1;
+                Ok(())
+            }
+            Some(c) if c == close => Ok(()),
+            _ => bail!("line {}: expected `,` or `{close}`", self.number),
+        }
+    }
+
+    fn plain(&mut self, in_flow: bool, key: bool) -> String {
+        let rest = &self.text[self.pos..];
+        let end = rest
+            .find(|c| (in_flow && [',', '}', ']'].contains(&c)) || (key && c == ':'))
+            .unwrap_or(rest.len());
+        self.pos += end;
+        rest[..end].trim().to_string()
+    }
+
+    fn single_quoted(&mut self) -> Result<String> {
+        self.pos += 1;
+        let mut value = String::new();
+        loop {
+            let rest = &self.text[self.pos..];
+            let Some(end) = rest.find('\'') else {
+                bail!("line {}: unterminated string", self.number);
+            };
+            value.push_str(&rest[..end]);
+            self.pos += end + 1;
+            // a quote is escaped by doubling it
+            if self.peek() != Some('\'') {
+                return Ok(value);
+            }
+            value.push('\'');
+            self.pos += 1;
+        }
+    }
+
+    fn double_quoted(&mut self) -> Result<String> {
+        self.pos += 1;
+        let mut value = String::new();
+        let mut chars = self.text[self.pos..].char_indices();
+        while let Some((index, c)) = chars.next() {
+            match c {
+                '"' => {
+                    self.pos += index + 1;
+                    return Ok(value);
+                }
+                '\\' => {
+                    let escaped = match chars.next().map(|(_, c)| c) {
+                        Some('0') => '\0',
+                        Some('a') => '\x07',
+                        Some('b') => '\x08',
+                        Some('t') => '\t',
+                        Some('n') => '\n',
+                        Some('v') => '\x0b',
+                        Some('f') => '\x0c',
+                        Some('r') => '\r',
+                        Some('e') => '\x1b',
+                        Some('x') => self.hex_escape(&mut chars, 2)?,
+                        Some('u') => self.hex_escape(&mut chars, 4)?,
+                        Some('U') => self.hex_escape(&mut chars, 8)?,
+                        Some(c @ (' ' | '"' | '/' | '\\')) => c,
+                        _ => bail!("line {}: unknown escape in string", self.number),
+                    };
+                    value.push(escaped);
+                }
+                c => value.push(c),
+            }
+        }
+
+        bail!("line {}: unterminated string", self.number)
+    }
+
+    fn hex_escape(&self, chars: &mut std::str::CharIndices, len: usize) -> Result<char> {
+        let digits: String = chars.take(len).map(|(_, c)| c).collect();
+        let code = u32::from_str_radix(&digits, 16).ok();
+        // unity writes characters outside the BMP as utf-16 surrogate pairs
+        let code = match code {
+            Some(high @ 0xD800..=0xDBFF) => {
+                let rest: String = chars.take(6).map(|(_, c)| c).collect();
+                let low = rest
+                    .strip_prefix("\\u")
+                    .and_then(|low| u32::from_str_radix(low, 16).ok())
+                    .filter(|low| (0xDC00..=0xDFFF).contains(low));
+                low.map(|low| 0x10000 + ((high - 0xD800) << 10) + (low - 0xDC00))
+            }
+            code => code,
+        };
+
+        code.and_then(char::from_u32)
+            .with_context(|| format!("line {}: invalid escape in string", self.number))
+    }
+}
@@ -2,7 +2,7 @@ using Unity.Entities;
 using UnityEngine;
 using unrust.runtime;
 
-namespace unrust.userland
+namespace unrust.userland.types
 {
     public class CubePrefabsAuthoring : MonoBehaviour {
         public GameObject HelloCube;
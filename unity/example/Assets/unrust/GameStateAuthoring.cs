@@ -2,7 +2,7 @@ using System.Runtime.InteropServices;
 using Unity.Entities;
 using UnityEngine;
 
-namespace unrust.userland
+namespace unrust.userland.types
 {
     [StructLayout(LayoutKind.Sequential)]
     public struct GameState : IComponentData
@@ -1,4 +1,5 @@
 using System.Runtime.InteropServices;
+using unrust.userland.types;
 
 namespace unrust.userland
 {
@@ -1,6 +1,8 @@
+using Unity.Collections.LowLevel.Unsafe;
 using Unity.Entities;
 using UnityEngine;
 using unrust.runtime;
+using unrust.userland.types;
 
 namespace unrust.userland
 {
@@ -9,6 +11,7 @@ namespace unrust.userland
         [RuntimeInitializeOnLoadMethod(RuntimeInitializeLoadType.BeforeSceneLoad)]
         static void Initialize()
         {
+            Debug.Assert(UnsafeUtility.SizeOf<DoRotate>() == 4, "DoRotate is 4 bytes in rust, rerun the unrust codegen");
             NativeWrapper.CustomCreates = CreateCallback;
         }
 
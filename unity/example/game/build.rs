@@ -1,3 +1,3 @@
 fn main() -> anyhow::Result<()> {
-    unrust::generate_csharp("./src/types.rs", "../Assets/unrust")
+    unrust::generate_csharp("./src/lib.rs", "../Assets/unrust")
 }
@@ -11,11 +11,11 @@ mod hello_cube_parenting;
 mod hello_cube_prefab;
 mod hello_cube_simple;
 
-#[unrust_setup((
-    (DoRotate,),
-    (GameState,),
-    (CubePrefabs,),
-))]
+#[unrust_setup(
+    components = [DoRotate],
+    states = [GameState],
+    prefabs = [CubePrefabs],
+)]
 pub fn setup(app: &mut App) {
     app.add_systems(
         Update,
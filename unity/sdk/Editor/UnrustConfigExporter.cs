@@ -0,0 +1,48 @@
+using System.IO;
+using System.Linq;
+using UnityEditor;
+using unrust.runtime;
+
+namespace unrust.editor
+{
+    // Exports every UnrustConfig to `<asset name>.<extension>` next to it whenever it is saved,
+    // which is the file rust loads through the AssetServer.
+    public class UnrustConfigExporter : AssetPostprocessor
+    {
+        static void OnPostprocessAllAssets(string[] imported, string[] deleted, string[] moved, string[] movedFrom)
+        {
+            foreach (var path in imported.Concat(moved))
+            {
+                if (AssetDatabase.LoadMainAssetAtPath(path) is UnrustConfig config)
+                {
+                    Export(path, config);
+                }
+            }
+        }
+
+        [MenuItem("unrust/Export Configs")]
+        static void ExportAll()
+        {
+            foreach (var guid in AssetDatabase.FindAssets($"t:{nameof(UnrustConfig)}"))
+            {
+                var path = AssetDatabase.GUIDToAssetPath(guid);
+                Export(path, AssetDatabase.LoadAssetAtPath<UnrustConfig>(path));
+            }
+        }
+
+        static void Export(string path, UnrustConfig config)
+        {
+            var output = Path.ChangeExtension(path, config.Extension);
+            var bytes = config.Export();
+
+            // rewriting an unchanged file would make bevy reload it for nothing
+            if (File.Exists(output) && File.ReadAllBytes(output).SequenceEqual(bytes))
+            {
+                return;
+            }
+
+            File.WriteAllBytes(output, bytes);
+            AssetDatabase.ImportAsset(output);
+        }
+    }
+}
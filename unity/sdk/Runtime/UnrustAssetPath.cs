@@ -0,0 +1,42 @@
+using Unity.Collections;
+using UnityEngine;
+
+#if UNITY_EDITOR
+using UnityEditor;
+#endif
+
+namespace unrust.runtime
+{
+    public static class UnrustAssetPath
+    {
+        private const string AssetsFolder = "Assets/";
+
+        // Handle and AssetPath fields are baked to the asset's path relative to Application.dataPath,
+        // which is the folder bevy's AssetServer loads from. Baking only runs in the editor.
+        public static FixedString128Bytes From(Object asset)
+        {
+            var result = new FixedString128Bytes();
+#if UNITY_EDITOR
+            if (asset == null)
+            {
+                return result;
+            }
+
+            var path = AssetDatabase.GetAssetPath(asset);
+            if (!path.StartsWith(AssetsFolder))
+            {
+                Debug.LogWarning($"unrust: {asset.name} is not in the Assets folder, bevy can not load it");
+                return result;
+            }
+
+            // a cut path would load the wrong file, send nothing instead
+            if (result.CopyFromTruncated(path.Substring(AssetsFolder.Length)) == CopyError.Truncation)
+            {
+                Debug.LogError($"unrust: the path of {asset.name} is longer than {result.Capacity} bytes");
+                return new FixedString128Bytes();
+            }
+#endif
+            return result;
+        }
+    }
+}
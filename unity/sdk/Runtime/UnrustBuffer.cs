@@ -0,0 +1,21 @@
+using Unity.Entities;
+
+namespace unrust.runtime
+{
+    // the elements of a DynamicBuffer, laid out like unrust::UnityBuffer in rust.
+    // Vec fields hold one, pointing at the entity's buffer while it is sent to rust.
+    public unsafe struct UnrustBuffer
+    {
+        public void* Ptr;
+        public nuint Length;
+
+        public static UnrustBuffer From<T>(DynamicBuffer<T> buffer) where T : unmanaged, IBufferElementData
+        {
+            return new UnrustBuffer
+            {
+                Ptr = buffer.GetUnsafeReadOnlyPtr(),
+                Length = (nuint)buffer.Length,
+            };
+        }
+    }
+}
@@ -0,0 +1,26 @@
+using Unity.Collections.LowLevel.Unsafe;
+using UnityEngine;
+
+namespace unrust.runtime
+{
+    // The base of the ScriptableObjects generated for #[unity_config] structs. The editor exports
+    // them next to their .asset as the bytes of the rust struct, which bevy loads and hot reloads.
+    public abstract class UnrustConfig : ScriptableObject
+    {
+        // eg: level_settings.bytes, the extension the rust loader is registered for
+        public abstract string Extension { get; }
+
+        public abstract byte[] Export();
+
+        protected static unsafe byte[] ToBytes<T>(T value) where T : unmanaged
+        {
+            var bytes = new byte[UnsafeUtility.SizeOf<T>()];
+            fixed (byte* ptr = bytes)
+            {
+                UnsafeUtility.CopyStructureToPtr(ref value, ptr);
+            }
+
+            return bytes;
+        }
+    }
+}
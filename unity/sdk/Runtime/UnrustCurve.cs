@@ -0,0 +1,55 @@
+using UnityEngine;
+
+namespace unrust.runtime
+{
+    // an AnimationCurve sampled between its first and last key, laid out like unrust::UnityCurve
+    public unsafe struct UnrustCurve
+    {
+        public const int SampleCount = 32;
+
+        public float Start;
+        public float End;
+        public fixed float Samples[SampleCount];
+
+        public static UnrustCurve From(AnimationCurve curve)
+        {
+            var result = new UnrustCurve();
+            if (curve == null || curve.length == 0)
+            {
+                return result;
+            }
+
+            result.Start = curve[0].time;
+            result.End = curve[curve.length - 1].time;
+            for (var i = 0; i < SampleCount; i++)
+            {
+                result.Samples[i] = curve.Evaluate(Mathf.Lerp(result.Start, result.End, i / (float)(SampleCount - 1)));
+            }
+
+            return result;
+        }
+    }
+
+    // a Gradient sampled from 0 to 1 as srgb rgba, laid out like unrust::UnityGradient
+    public unsafe struct UnrustGradient
+    {
+        public const int SampleCount = 16;
+
+        public fixed float Samples[SampleCount * 4];
+
+        public static UnrustGradient From(Gradient gradient)
+        {
+            var result = new UnrustGradient();
+            for (var i = 0; i < SampleCount; i++)
+            {
+                var color = gradient != null ? gradient.Evaluate(i / (float)(SampleCount - 1)) : Color.white;
+                result.Samples[i * 4] = color.r;
+                result.Samples[i * 4 + 1] = color.g;
+                result.Samples[i * 4 + 2] = color.b;
+                result.Samples[i * 4 + 3] = color.a;
+            }
+
+            return result;
+        }
+    }
+}
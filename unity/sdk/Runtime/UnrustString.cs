@@ -0,0 +1,21 @@
+using Unity.Collections;
+using UnityEngine;
+
+namespace unrust.runtime
+{
+    public static class UnrustString
+    {
+        // String fields are baked into a FixedString64Bytes, which holds 61 bytes of utf-8.
+        // Longer strings are cut at a character boundary instead of failing the bake.
+        public static FixedString64Bytes From(string value)
+        {
+            var result = new FixedString64Bytes();
+            if (value != null && result.CopyFromTruncated(value) == CopyError.Truncation)
+            {
+                Debug.LogWarning($"unrust: \"{value}\" is longer than {result.Capacity} bytes and was cut to \"{result}\"");
+            }
+
+            return result;
+        }
+    }
+}